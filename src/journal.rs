@@ -0,0 +1,200 @@
+use serde::Deserialize;
+use serde::Serialize;
+use std::fs::File;
+use std::path::Path;
+use unreal_asset::types::fname::FName;
+
+use crate::Vec3d;
+
+/// Record of every change made to an asset in one run, written next to the output so the run can
+/// be reverted with --undo
+#[derive(Serialize, Deserialize, Default)]
+pub struct Journal {
+    pub input: String,
+    pub entries: Vec<JournalEntry>,
+}
+
+/// A name as stored in an FName, with its number kept separately
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct JournalName {
+    pub name: String,
+    pub number: i32,
+}
+
+impl From<&FName> for JournalName {
+    fn from(fname: &FName) -> JournalName {
+        JournalName {
+            name: fname.get_owned_content(),
+            number: fname.get_number(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum JournalValue {
+    Name(JournalName),
+    Vector(Vec3d),
+}
+
+/// Indices are the ones in the asset at the time of the change
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum JournalEntry {
+    ImportOuter {
+        import: i32,
+        old: i32,
+        new: i32,
+    },
+    ImportName {
+        import: i32,
+        old: JournalName,
+        new: JournalName,
+    },
+    ExportName {
+        export: i32,
+        old: JournalName,
+        new: JournalName,
+    },
+    /// A name map entry containing the package name, renamed to match the output file name
+    PackageName {
+        old: String,
+        new: String,
+    },
+    LevelActors {
+        level: i32,
+        old_actors: Vec<i32>,
+        new_actors: Vec<i32>,
+        old_dependencies: Vec<i32>,
+        new_dependencies: Vec<i32>,
+    },
+    Property {
+        export: i32,
+        path: Vec<String>,
+        old: JournalValue,
+        new: JournalValue,
+    },
+    /// old is None if the property was added
+    Transform {
+        component: i32,
+        property: String,
+        old: Option<Vec3d>,
+        new: Vec3d,
+    },
+    /// Exports or imports were added or removed, which renumbers everything after them
+    Structural {
+        description: String,
+    },
+}
+
+impl std::fmt::Display for JournalName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.number {
+            0 => write!(f, "{}", self.name),
+            number => write!(f, "{}_{}", self.name, number - 1),
+        }
+    }
+}
+
+impl std::fmt::Display for JournalValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JournalValue::Name(name) => write!(f, "{}", name),
+            JournalValue::Vector(v) => write!(f, "{}", v),
+        }
+    }
+}
+
+impl std::fmt::Display for JournalEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JournalEntry::ImportOuter { import, old, new } => {
+                write!(f, "import {} outer: {} -> {}", import, old, new)
+            }
+            JournalEntry::ImportName { import, old, new } => {
+                write!(f, "import {} name: {} -> {}", import, old, new)
+            }
+            JournalEntry::ExportName { export, old, new } => {
+                write!(f, "export {} name: {} -> {}", export, old, new)
+            }
+            JournalEntry::PackageName { old, new } => write!(f, "name: {} -> {}", old, new),
+            JournalEntry::LevelActors {
+                level,
+                old_actors,
+                new_actors,
+                ..
+            } => {
+                let removed: Vec<_> = old_actors
+                    .iter()
+                    .filter(|i| !new_actors.contains(i))
+                    .collect();
+                let added: Vec<_> = new_actors
+                    .iter()
+                    .filter(|i| !old_actors.contains(i))
+                    .collect();
+                write!(
+                    f,
+                    "level {} actors: removed {:?}, added {:?}",
+                    level, removed, added
+                )
+            }
+            JournalEntry::Property {
+                export,
+                path,
+                old,
+                new,
+            } => write!(
+                f,
+                "export {} {}: {} -> {}",
+                export,
+                path.join("."),
+                old,
+                new
+            ),
+            JournalEntry::Transform {
+                component,
+                property,
+                old,
+                new,
+            } => match old {
+                Some(old) => write!(f, "export {} {}: {} -> {}", component, property, old, new),
+                None => write!(f, "export {} {}: (added) -> {}", component, property, new),
+            },
+            JournalEntry::Structural { description } => write!(f, "{}", description),
+        }
+    }
+}
+
+impl Journal {
+    pub fn new(input: &str) -> Journal {
+        Journal {
+            input: input.to_string(),
+            entries: vec![],
+        }
+    }
+
+    pub fn record(&mut self, entry: JournalEntry) {
+        self.entries.push(entry);
+    }
+
+    pub fn load(path: &Path) -> Journal {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(err) => {
+                eprintln!("cannot open undo journal {}: {}", path.display(), err);
+                panic!();
+            }
+        };
+        match serde_json::from_reader(file) {
+            Ok(journal) => journal,
+            Err(err) => {
+                eprintln!("cannot parse undo journal {}: {}", path.display(), err);
+                panic!();
+            }
+        }
+    }
+
+    pub fn save(&self, path: &Path) {
+        let file = File::create(path).unwrap();
+        serde_json::to_writer_pretty(file, self).unwrap();
+    }
+}
@@ -1,22 +1,43 @@
+mod journal;
+mod roundtrip;
+mod validate;
+
 use clap::Parser;
+use clap::ValueEnum;
+use journal::Journal;
+use journal::JournalEntry;
+use journal::JournalName;
+use journal::JournalValue;
+use serde::Deserialize;
+use serde::Serialize;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::fs;
 use std::fs::File;
 use std::path::Path;
+use unreal_asset::exports::struct_export::StructExport;
 use unreal_asset::exports::Export;
 use unreal_asset::exports::ExportBaseTrait;
 use unreal_asset::exports::ExportNormalTrait;
-use unreal_asset::properties::object_property::ObjectProperty;
-use unreal_asset::properties::str_property::NameProperty;
+use unreal_asset::fproperty::FProperty;
+use unreal_asset::properties::int_property::BytePropertyValue;
+use unreal_asset::properties::soft_path_property::SoftObjectPathPropertyValue;
+use unreal_asset::properties::struct_property::StructProperty;
+use unreal_asset::properties::vector_property::RotatorProperty;
+use unreal_asset::properties::vector_property::VectorProperty;
 use unreal_asset::properties::Property;
+use unreal_asset::properties::PropertyDataTrait;
+use unreal_asset::types::fname::FName;
+use unreal_asset::types::vector::Vector;
 use unreal_asset::types::PackageIndex;
 use unreal_asset::Asset;
+use unreal_asset::Import;
 
 /// Edit cooked Unreal Engine assets
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// Path to input uasset file
+    /// Path to input uasset file (or folder, with --roundtrip)
     #[arg(short, long)]
     input: String,
 
@@ -28,6 +49,16 @@ struct Args {
     #[arg(short, long)]
     disable_import: Vec<String>,
 
+    /// Name or index of import to re-enable by restoring its outer (syntax: name to infer the
+    /// outer package, or name>outer where outer is an import name or index)
+    #[arg(long)]
+    enable_import: Vec<String>,
+
+    /// Name or index of import to remove (syntax: name, name>null to null references that
+    /// aren't a class, template or outer, or name>othername to reroute references)
+    #[arg(long)]
+    remove_import: Vec<String>,
+
     /// Name of import to rename (syntax: oldname>newname)
     #[arg(short, long)]
     rename_import: Vec<String>,
@@ -40,21 +71,181 @@ struct Args {
     #[arg(long)]
     disable_actor_by_index: Vec<String>,
 
+    /// Class of actors to disable (also matches Blueprint subclasses)
+    #[arg(long)]
+    disable_actor_by_class: Vec<String>,
+
+    /// Name or index of an actor export to add back to its level's actor list
+    #[arg(long)]
+    enable_actor: Vec<String>,
+
+    /// Name of level actor to delete along with its subobjects, where BP_Foo_C_3 is one actor and
+    /// BP_Foo_C is every numbered instance
+    #[arg(long)]
+    delete_actor_by_name: Vec<String>,
+
+    /// Index of level actor to delete along with its subobjects
+    #[arg(long)]
+    delete_actor_by_index: Vec<String>,
+
+    /// Class of actors to delete along with their subobjects (also matches Blueprint subclasses)
+    #[arg(long)]
+    delete_actor_by_class: Vec<String>,
+
+    /// Name or index of actor to copy within the same level along with its subobjects, where
+    /// BP_Foo_C_3 is one actor and BP_Foo_C is every numbered instance (syntax: 42, 42*10 for ten
+    /// copies, or 42*10>dx,dy,dz to offset each copy from the previous one)
+    #[arg(long)]
+    duplicate_actor: Vec<String>,
+
+    /// Import to add (syntax: classpackage,classname,outer,objectname where outer is a package
+    /// name or a negative import index)
+    #[arg(long)]
+    add_import: Vec<String>,
+
+    /// Name or export index of the level that actors are disabled in, transplanted into, or
+    /// selected from by region (defaults to PersistentLevel)
+    #[arg(long)]
+    level: Option<String>,
+
     /// Export index and property to edit (syntax: 42.propname=newvalue)
     #[arg(long)]
     edit_export: Vec<String>,
 
+    /// Class and property to edit on every actor of that class, where RelativeLocation,
+    /// RelativeRotation and RelativeScale3D are edited on the actor's root component (syntax:
+    /// BP_Foo_C.propname=newvalue)
+    #[arg(long)]
+    edit_actors_by_class: Vec<String>,
+
+    /// Print out every actor of a class (also matches Blueprint subclasses)
+    #[arg(long)]
+    list_actors_by_class: Vec<String>,
+
+    /// Region in which to disable every actor, based on root component location (syntax:
+    /// box:x1,y1,z1,x2,y2,z2 or sphere:x,y,z,radius)
+    #[arg(long)]
+    disable_actors_in: Vec<String>,
+
+    /// Region in which to delete every actor along with its subobjects (syntax: same as
+    /// --disable-actors-in)
+    #[arg(long)]
+    delete_actors_in: Vec<String>,
+
+    /// Region in which to move every actor by an offset (syntax: box:x1,y1,z1,x2,y2,z2>dx,dy,dz
+    /// or sphere:x,y,z,radius>dx,dy,dz)
+    #[arg(long)]
+    move_actors_in: Vec<String>,
+
+    /// Actor to move, by export index or name, where BP_Foo_C_3 is one actor and BP_Foo_C is
+    /// every numbered instance (syntax: actor=x,y,z to set the location or actor+=dx,dy,dz to
+    /// offset it)
+    #[arg(long)]
+    move_actor: Vec<String>,
+
+    /// Actor to rotate, by export index or name like --move-actor (syntax: actor=pitch,yaw,roll
+    /// or actor+=dpitch,dyaw,droll)
+    #[arg(long)]
+    rotate_actor: Vec<String>,
+
+    /// Actor to scale, by export index or name like --move-actor (syntax: actor=x,y,z or
+    /// actor+=dx,dy,dz)
+    #[arg(long)]
+    scale_actor: Vec<String>,
+
+    /// Print out every placed actor with its class, transform and component count, including
+    /// actors that are missing from their level's actor list
+    #[arg(long, default_value_t = false)]
+    list_actors: bool,
+
+    /// Read every asset in the input folder and write it back unchanged, reporting any asset
+    /// whose output isn't byte for byte identical
+    #[arg(long, default_value_t = false)]
+    roundtrip: bool,
+
+    /// Check asset for broken references, missing names and other problems left by edits. The
+    /// same checks run on every edited asset, which is not written if edits added an error
+    #[arg(long, default_value_t = false)]
+    validate: bool,
+
+    /// Output format for --list-actors and --validate
+    #[arg(long, value_enum, default_value_t = ReportFormat::Table)]
+    format: ReportFormat,
+
+    /// Overwrite input instead of writing to --output, keeping the original as .uasset.bak (or
+    /// .umap.bak) and .uexp.bak, or .bak.1, .bak.2 and so on if earlier backups exist. The undo
+    /// journal is numbered the same way: .undo.json, .undo.1.json and so on.
+    #[arg(long, default_value_t = false)]
+    in_place: bool,
+
+    /// Make every selection and edit without writing anything, then print what would change
+    #[arg(long, default_value_t = false)]
+    dry_run: bool,
+
+    /// Undo journal written by an earlier run, to revert the changes it recorded on input
+    #[arg(long)]
+    undo: Option<String>,
+
     /// Print out every import and export in asset
     #[arg(long, default_value_t = false)]
     dump: bool,
 
-    /// Uasset file to extract actors from
+    /// Uasset file to extract actors from. Blueprint classes, functions, structs and property
+    /// definitions, levels and exports that couldn't be parsed can't be transplanted.
     #[arg(long)]
     transplant_donor: Option<String>,
 
     /// Actor to extract from transplant donor
     #[arg(long)]
     actor_to_transplant: Vec<i32>,
+
+    /// Name of actor to extract from transplant donor (name may match multiple actors)
+    #[arg(long)]
+    actor_to_transplant_by_name: Vec<String>,
+
+    /// Class of actors to extract from transplant donor (also matches Blueprint subclasses)
+    #[arg(long)]
+    actor_to_transplant_by_class: Vec<String>,
+
+    /// Export to copy from transplant donor along with its subobjects, without adding it to a
+    /// level (syntax: donorindex>outer or donorname>outer where outer is an export index or 0)
+    #[arg(long, requires = "transplant_donor")]
+    copy_export: Vec<String>,
+
+    /// New name for a transplanted actor (syntax: oldname>newname)
+    #[arg(long)]
+    transplant_rename: Vec<String>,
+
+    /// Offset added to the location of transplanted actors (syntax: x,y,z)
+    #[arg(long)]
+    transplant_location_offset: Option<String>,
+
+    /// Offset added to the rotation of transplanted actors (syntax: pitch,yaw,roll)
+    #[arg(long)]
+    transplant_rotation_offset: Option<String>,
+
+    /// Factors multiplied into the scale of transplanted actors (syntax: x,y,z)
+    #[arg(long)]
+    transplant_scale: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum ReportFormat {
+    Table,
+    Csv,
+    Json,
+}
+
+#[derive(Serialize)]
+struct ActorReport {
+    level: i32,
+    index: i32,
+    name: String,
+    class: String,
+    location: Option<Vec3d>,
+    rotation: Option<Vec3d>,
+    components: usize,
+    in_actors_list: bool,
 }
 
 enum PropType {
@@ -62,34 +253,108 @@ enum PropType {
     Name,
 }
 
-#[derive(Debug, Default)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
 struct Vec3d {
     x: f64,
     y: f64,
     z: f64,
 }
 
+impl Vec3d {
+    // e.g. 1,2,3
+    fn parse(s: &str) -> Vec3d {
+        let fields: Vec<_> = s.split(",").collect();
+        if fields.len() != 3 {
+            eprintln!("'{}' should have 3 comma-separated components", s);
+            panic!();
+        }
+        Vec3d {
+            x: fields[0].parse::<f64>().unwrap(),
+            y: fields[1].parse::<f64>().unwrap(),
+            z: fields[2].parse::<f64>().unwrap(),
+        }
+    }
+}
+
 impl std::fmt::Display for Vec3d {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{},{},{}", self.x, self.y, self.z)
     }
 }
 
+enum Region {
+    Box { min: Vec3d, max: Vec3d },
+    Sphere { center: Vec3d, radius: f64 },
+}
+
+impl Region {
+    // e.g. box:-100,-100,0,100,100,500
+    // e.g. sphere:0,0,0,1000
+    fn parse(s: &str) -> Region {
+        let Some((kind, values)) = s.split_once(":") else {
+            eprintln!("region '{}' should start with box: or sphere:", s);
+            panic!();
+        };
+        let values: Vec<f64> = values
+            .split(",")
+            .map(|v| v.parse::<f64>().unwrap())
+            .collect();
+        match (kind, values.len()) {
+            ("box", 6) => Region::Box {
+                min: Vec3d {
+                    x: values[0].min(values[3]),
+                    y: values[1].min(values[4]),
+                    z: values[2].min(values[5]),
+                },
+                max: Vec3d {
+                    x: values[0].max(values[3]),
+                    y: values[1].max(values[4]),
+                    z: values[2].max(values[5]),
+                },
+            },
+            ("sphere", 4) => Region::Sphere {
+                center: Vec3d {
+                    x: values[0],
+                    y: values[1],
+                    z: values[2],
+                },
+                radius: values[3],
+            },
+            _ => {
+                eprintln!("region '{}' has unrecognized format", s);
+                panic!();
+            }
+        }
+    }
+
+    fn contains(&self, v: &Vec3d) -> bool {
+        match self {
+            Region::Box { min, max } => {
+                (min.x..=max.x).contains(&v.x)
+                    && (min.y..=max.y).contains(&v.y)
+                    && (min.z..=max.z).contains(&v.z)
+            }
+            Region::Sphere { center, radius } => {
+                let dx = v.x - center.x;
+                let dy = v.y - center.y;
+                let dz = v.z - center.z;
+                dx * dx + dy * dy + dz * dz <= radius * radius
+            }
+        }
+    }
+}
+
 fn main() {
     let args = Args::parse();
 
     let input_uasset_path = Path::new(&args.input);
-    let input_uasset_file = File::open(input_uasset_path).unwrap();
-    let input_uexp_path = input_uasset_path.with_extension("uexp");
-    let input_uexp_file_maybe = File::open(input_uexp_path).ok();
-
-    let mut asset = Asset::new(
-        input_uasset_file,
-        input_uexp_file_maybe,
-        unreal_asset::engine_version::EngineVersion::VER_UE5_1,
-        None,
-    )
-    .unwrap();
+    if args.roundtrip {
+        if !roundtrip::roundtrip_folder(input_uasset_path) {
+            std::process::exit(1);
+        }
+        return;
+    }
+    let mut asset = open_asset(input_uasset_path).unwrap();
 
     if args.dump {
         for (i, import) in asset.imports.iter().enumerate() {
@@ -148,10 +413,134 @@ fn main() {
         return;
     }
 
-    let output_uasset_path = Path::new(args.output.as_ref().unwrap());
-    let mut output_uasset_file = File::create(output_uasset_path).unwrap();
-    let output_uexp_path = output_uasset_path.with_extension("uexp");
-    let mut output_uexp_file = File::create(output_uexp_path).unwrap();
+    if args.list_actors {
+        let reports = list_actors(&asset);
+        match args.format {
+            ReportFormat::Table => {
+                println!(
+                    "{:>5} {:>6}  {:<40} {:<32} {:<28} {:<28} {:>5}",
+                    "level", "index", "name", "class", "location", "rotation", "comps"
+                );
+                for report in &reports {
+                    let format_vec = |v: &Option<Vec3d>| match v {
+                        Some(v) => format!("{:.2},{:.2},{:.2}", v.x, v.y, v.z),
+                        None => "-".to_string(),
+                    };
+                    println!(
+                        "{:>5} {:>6}  {:<40} {:<32} {:<28} {:<28} {:>5}{}",
+                        report.level,
+                        report.index,
+                        report.name,
+                        report.class,
+                        format_vec(&report.location),
+                        format_vec(&report.rotation),
+                        report.components,
+                        if report.in_actors_list {
+                            ""
+                        } else {
+                            "  (not in actors list)"
+                        }
+                    );
+                }
+            }
+            ReportFormat::Csv => {
+                println!("level,index,name,class,x,y,z,pitch,yaw,roll,components,in_actors_list");
+                for report in &reports {
+                    let format_vec = |v: &Option<Vec3d>| match v {
+                        Some(v) => format!("{},{},{}", v.x, v.y, v.z),
+                        None => ",,".to_string(),
+                    };
+                    println!(
+                        "{},{},{},{},{},{},{},{}",
+                        report.level,
+                        report.index,
+                        report.name,
+                        report.class,
+                        format_vec(&report.location),
+                        format_vec(&report.rotation),
+                        report.components,
+                        report.in_actors_list
+                    );
+                }
+            }
+            ReportFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(&reports).unwrap());
+            }
+        }
+        return;
+    }
+
+    if args.validate {
+        let findings = validate::validate(&asset);
+        match args.format {
+            ReportFormat::Table => {
+                for finding in &findings {
+                    println!("{}: {}", finding.severity, finding.message);
+                }
+                let num_errors = findings
+                    .iter()
+                    .filter(|finding| finding.severity == validate::Severity::Error)
+                    .count();
+                println!(
+                    "{} errors, {} warnings",
+                    num_errors,
+                    findings.len() - num_errors
+                );
+            }
+            ReportFormat::Csv => {
+                println!("severity,message");
+                for finding in &findings {
+                    println!(
+                        "{},\"{}\"",
+                        finding.severity,
+                        finding.message.replace('"', "\"\"")
+                    );
+                }
+            }
+            ReportFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(&findings).unwrap());
+            }
+        }
+        if findings
+            .iter()
+            .any(|finding| finding.severity == validate::Severity::Error)
+        {
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if !args.list_actors_by_class.is_empty() {
+        for class_name in &args.list_actors_by_class {
+            for index in find_actors_by_class(&asset, class_name) {
+                let base_export = asset
+                    .get_export(PackageIndex::new(index))
+                    .unwrap()
+                    .get_base_export();
+                println!(
+                    "{}: {} ({})",
+                    index,
+                    base_export.object_name.get_owned_content(),
+                    get_class_chain(&asset, base_export.class_index).join(" < ")
+                );
+            }
+        }
+        return;
+    }
+
+    // a dry run without --output keeps the package name
+    let output_uasset_path = match (&args.output, args.in_place, args.dry_run) {
+        (Some(_), true, _) => {
+            eprintln!("--output and --in-place can't be used together");
+            panic!();
+        }
+        (Some(output), false, _) => Path::new(output),
+        (None, true, _) | (None, false, true) => input_uasset_path,
+        (None, false, false) => {
+            eprintln!("--output is required unless --in-place or --dry-run is given");
+            panic!();
+        }
+    };
 
     let input_uasset_name = input_uasset_path
         .file_stem()
@@ -164,24 +553,45 @@ fn main() {
         .to_string_lossy()
         .to_string();
 
+    let mut journal = Journal::new(&args.input);
+    // only errors that edits add stop the asset from being written, not ones it already had
+    let input_errors: HashSet<String> = validate::validate(&asset)
+        .into_iter()
+        .filter(|finding| finding.severity == validate::Severity::Error)
+        .map(|finding| finding.message)
+        .collect();
+
     let name_map = asset.get_name_map();
     let num_names = name_map.borrow().get_name_map_index_list().len();
     for i in 0..(num_names as i32) {
         let name_copy = name_map.borrow().get_owned_name(i);
-        if name_copy.contains(&input_uasset_name) {
+        if name_copy.contains(&input_uasset_name) && input_uasset_name != output_uasset_name {
             let mut name_map = name_map.borrow_mut();
             let name_ref = name_map.get_name_reference_mut(i);
             name_ref.clear();
             name_ref.push_str(&name_copy.replace(&input_uasset_name, &output_uasset_name));
+            journal.record(JournalEntry::PackageName {
+                old: name_copy.clone(),
+                new: name_ref.clone(),
+            });
             println!("Updated FName: {} -> {}", name_copy, name_ref);
         }
     }
 
+    if let Some(undo) = &args.undo {
+        undo_journal(&mut asset, &Journal::load(Path::new(undo)));
+    }
+
     for disable_import in &args.disable_import {
-        for import in &mut asset.imports {
+        for (i, import) in asset.imports.iter_mut().enumerate() {
             if &import.object_name.get_owned_content() == disable_import {
                 let original_index = import.outer_index.index;
                 import.outer_index.index = 0;
+                journal.record(JournalEntry::ImportOuter {
+                    import: -(i as i32 + 1),
+                    old: original_index,
+                    new: 0,
+                });
                 println!(
                     "Updated import: {}: {} -> {}",
                     disable_import, original_index, import.outer_index.index
@@ -190,14 +600,108 @@ fn main() {
         }
     }
 
+    // e.g. BP_Foo_C
+    // e.g. BP_Foo_C>null
+    // e.g. BP_Foo_C>BP_Bar_C
+    // e.g. -12>-7
+    for remove_import in &args.remove_import {
+        let (name, replacement) = match remove_import.split_once(">") {
+            Some((name, replacement)) => (name, Some(replacement)),
+            None => (remove_import.as_str(), None),
+        };
+        let Some(index) = find_import_by_name(&asset, name) else {
+            eprintln!("Warning: import '{}' not found", name);
+            continue;
+        };
+        let replacement = match replacement {
+            None => None,
+            Some("null") => Some(PackageIndex::new(0)),
+            Some(replacement) => {
+                let Some(replacement) = find_import_by_name(&asset, replacement) else {
+                    eprintln!("replacement import '{}' not found", replacement);
+                    panic!();
+                };
+                if replacement.index == index.index {
+                    eprintln!("cannot reroute import '{}' to itself", name);
+                    panic!();
+                }
+                Some(replacement)
+            }
+        };
+        let mut references = vec![];
+        for_each_package_index(&mut asset, &mut |owner, field, package_index| {
+            if package_index.index == index.index {
+                references.push((owner, field.to_string()));
+            }
+        });
+        if !references.is_empty() {
+            let Some(replacement) = replacement else {
+                for (owner, field) in &references {
+                    eprintln!("  referenced by {}: {}", owner, field);
+                }
+                eprintln!(
+                    "import '{}' is still referenced; use {}>null or {}>othername",
+                    name, name, name
+                );
+                panic!();
+            };
+            // exports can't lose their class, template or outer and imports can't lose their
+            // outer, so those have to be rerouted to another import
+            let structural: Vec<_> = references
+                .iter()
+                .filter(|(_, field)| {
+                    [
+                        "class_index",
+                        "super_index",
+                        "template_index",
+                        "outer_index",
+                    ]
+                    .contains(&field.as_str())
+                })
+                .collect();
+            if replacement.index == 0 && !structural.is_empty() {
+                for (owner, field) in &structural {
+                    eprintln!("  referenced by {}: {}", owner, field);
+                }
+                eprintln!(
+                    "import '{}' is used as a class, template or outer and can't be nulled; use {}>othername",
+                    name, name
+                );
+                panic!();
+            }
+            for_each_package_index(&mut asset, &mut |_, _, package_index| {
+                if package_index.index == index.index {
+                    package_index.index = replacement.index;
+                }
+            });
+            remove_null_dependencies(&mut asset);
+            println!(
+                "Updated {} references: {} -> {}",
+                references.len(),
+                index.index,
+                replacement.index
+            );
+        }
+        remove_import_at(&mut asset, index);
+        journal.record(JournalEntry::Structural {
+            description: format!("removed import {} \"{}\"", index.index, name),
+        });
+        println!("Removed import: {}: {}", index.index, name);
+    }
+
     for rename_import in &args.rename_import {
         let mut tokens = rename_import.split(">");
         let old_name = tokens.next().unwrap();
         let new_name = tokens.next().unwrap();
         let new_fname = asset.add_fname(new_name);
         let mut import_found = false;
-        for import in &mut asset.imports {
+        for (i, import) in asset.imports.iter_mut().enumerate() {
             if &import.object_name.get_owned_content() == old_name {
+                journal.record(JournalEntry::ImportName {
+                    import: -(i as i32 + 1),
+                    old: JournalName::from(&import.object_name),
+                    new: JournalName::from(&new_fname),
+                });
                 import.object_name = new_fname;
                 import_found = true;
                 println!("Renamed import: {} -> {}", old_name, new_name);
@@ -209,6 +713,103 @@ fn main() {
         }
     }
 
+    // e.g. BP_Foo_C
+    // e.g. BP_Foo_C>/Game/Foo/BP_Foo
+    for enable_import in &args.enable_import {
+        let (name, outer) = match enable_import.split_once(">") {
+            Some((name, outer)) => (name, Some(outer)),
+            None => (enable_import.as_str(), None),
+        };
+        let Some(import_index) = find_import_by_name(&asset, name) else {
+            eprintln!("Warning: import '{}' not found", name);
+            continue;
+        };
+        let outer_index = match outer {
+            Some(outer) => {
+                let Some(outer_index) = find_import_by_name(&asset, outer) else {
+                    eprintln!("outer import '{}' not found", outer);
+                    panic!();
+                };
+                outer_index
+            }
+            None => {
+                let import = asset.get_import(import_index).unwrap();
+                if import.outer_index.index != 0 {
+                    eprintln!(
+                        "Warning: import '{}' already has outer {}",
+                        name, import.outer_index.index
+                    );
+                    continue;
+                }
+                let Some(outer_index) = infer_import_outer(&asset, import_index) else {
+                    eprintln!(
+                        "cannot infer an outer for import '{}', use name>outer instead",
+                        name
+                    );
+                    panic!();
+                };
+                outer_index
+            }
+        };
+        if outer_index.index == import_index.index {
+            eprintln!("import '{}' cannot be its own outer", name);
+            panic!();
+        }
+        let import = &mut asset.imports[(-import_index.index - 1) as usize];
+        let original_index = import.outer_index.index;
+        import.outer_index = outer_index;
+        journal.record(JournalEntry::ImportOuter {
+            import: import_index.index,
+            old: original_index,
+            new: outer_index.index,
+        });
+        println!(
+            "Updated import: {}: {} -> {}",
+            name, original_index, outer_index.index
+        );
+    }
+
+    // e.g. /Script/Engine,BlueprintGeneratedClass,/Game/Foo/BP_Foo,BP_Foo_C
+    // e.g. /Script/Engine,StaticMesh,-12,SM_Foo
+    for add_import in &args.add_import {
+        let fields: Vec<_> = add_import.split(",").collect();
+        if fields.len() != 4 {
+            eprintln!("add-import expects 4 comma-separated fields");
+            panic!();
+        }
+        let (class_package, class_name, outer, object_name) =
+            (fields[0], fields[1], fields[2], fields[3]);
+        let num_imports = asset.imports.len();
+        let outer_index = match outer.parse::<i32>() {
+            Ok(index) => {
+                if index >= 0 || asset.get_import(PackageIndex::new(index)).is_none() {
+                    eprintln!("outer '{}' is not a valid import index", outer);
+                    panic!();
+                }
+                PackageIndex::new(index)
+            }
+            Err(_) => find_or_add_import(
+                &mut asset,
+                "/Script/CoreUObject",
+                "Package",
+                PackageIndex::new(0),
+                outer,
+            ),
+        };
+        find_or_add_import(
+            &mut asset,
+            class_package,
+            class_name,
+            outer_index,
+            object_name,
+        );
+        if asset.imports.len() != num_imports {
+            journal.record(JournalEntry::Structural {
+                description: format!("added import \"{}\"", object_name),
+            });
+        }
+    }
+
     let mut actor_indices_to_disable = vec![];
     for actor in &args.disable_actor_by_name {
         for (i, export) in asset.asset_data.exports.iter().enumerate() {
@@ -221,11 +822,43 @@ fn main() {
         let i = usize::from_str_radix(i, 10).unwrap();
         actor_indices_to_disable.push(i - 1);
     }
+    for class_name in &args.disable_actor_by_class {
+        // actors are removed from the --level level only, so actors of other levels are skipped
+        let level_actors =
+            level_snapshot(&asset, find_level_index(&asset, args.level.as_deref())).0;
+        for i in find_actors_by_class(&asset, class_name) {
+            if level_actors.contains(&i) {
+                actor_indices_to_disable.push(i as usize - 1);
+            }
+        }
+    }
+    for region in &args.disable_actors_in {
+        let level_index = find_level_index(&asset, args.level.as_deref());
+        for i in find_actors_in_region(&asset, level_index, &Region::parse(region)) {
+            actor_indices_to_disable.push(i as usize - 1);
+        }
+    }
     if !actor_indices_to_disable.is_empty() {
+        let level_index = find_level_index(&asset, args.level.as_deref());
+        let level_name = asset
+            .get_export(level_index)
+            .unwrap()
+            .get_base_export()
+            .object_name
+            .get_owned_content();
+        let level_actors = level_snapshot(&asset, level_index).0;
         for index in &actor_indices_to_disable {
             let index = PackageIndex::new(*index as i32 + 1);
+            if !level_actors.contains(&index.index) {
+                eprintln!(
+                    "Warning: export {} is not an actor of {}",
+                    index.index, level_name
+                );
+                continue;
+            }
             println!(
-                "Removed actor from PersistentLevel: {}: {}",
+                "Removed actor from {}: {}: {}",
+                level_name,
                 index.index,
                 asset
                     .get_export(index)
@@ -239,10 +872,8 @@ fn main() {
             .into_iter()
             .map(|i| i as i32 + 1)
             .collect();
-        let persistent_level_index = find_persistent_level_index(&asset).unwrap();
-        if let Export::LevelExport(persistent_level) =
-            asset.get_export_mut(persistent_level_index).unwrap()
-        {
+        let (old_actors, old_dependencies) = level_snapshot(&asset, level_index);
+        if let Export::LevelExport(persistent_level) = asset.get_export_mut(level_index).unwrap() {
             persistent_level.actors = persistent_level
                 .actors
                 .clone()
@@ -252,6 +883,76 @@ fn main() {
         } else {
             panic!();
         }
+        let (new_actors, new_dependencies) = level_snapshot(&asset, level_index);
+        journal.record(JournalEntry::LevelActors {
+            level: level_index.index,
+            old_actors,
+            new_actors,
+            old_dependencies,
+            new_dependencies,
+        });
+    }
+
+    for actor in &args.enable_actor {
+        let actor_indices: Vec<i32> = match actor.parse::<i32>() {
+            Ok(index) => vec![index],
+            Err(_) => asset
+                .asset_data
+                .exports
+                .iter()
+                .enumerate()
+                .filter(|(_, export)| {
+                    let object_name = &export.get_base_export().object_name;
+                    object_name.get_owned_content() == *actor
+                        || fname_to_string(object_name) == *actor
+                })
+                .map(|(i, _)| i as i32 + 1)
+                .collect(),
+        };
+        if actor_indices.is_empty() {
+            eprintln!("Warning: no exports named '{}' found", actor);
+        }
+        for actor_index in actor_indices {
+            let Some(actor_export) = asset.get_export(PackageIndex::new(actor_index)) else {
+                eprintln!("export {} not found", actor_index);
+                panic!();
+            };
+            let level_index = actor_export.get_base_export().outer_index;
+            let Some(Export::LevelExport(level)) = asset.get_export(level_index) else {
+                eprintln!("export {} is not an actor in a level", actor_index);
+                panic!();
+            };
+            if level.actors.iter().any(|a| a.index == actor_index) {
+                eprintln!("Warning: actor {} is already enabled", actor_index);
+                continue;
+            }
+            let (old_actors, old_dependencies) = level_snapshot(&asset, level_index);
+            if let Some(Export::LevelExport(level)) = asset.get_export_mut(level_index) {
+                level.actors.push(PackageIndex::new(actor_index));
+            }
+            rebuild_level_dependencies(&mut asset, level_index);
+            let (new_actors, new_dependencies) = level_snapshot(&asset, level_index);
+            journal.record(JournalEntry::LevelActors {
+                level: level_index.index,
+                old_actors,
+                new_actors,
+                old_dependencies,
+                new_dependencies,
+            });
+            let level_export = asset.get_export(level_index).unwrap().get_base_export();
+            println!(
+                "Added actor to {}: {}: {}",
+                level_export.object_name.get_owned_content(),
+                actor_index,
+                fname_to_string(
+                    &asset
+                        .get_export(PackageIndex::new(actor_index))
+                        .unwrap()
+                        .get_base_export()
+                        .object_name
+                )
+            );
+        }
     }
 
     // split at equal sign and parse left and right side separately
@@ -262,29 +963,6 @@ fn main() {
             panic!();
         };
         let lhs_fields: Vec<_> = lhs.split(".").collect();
-        let rhs_fields: Vec<_> = rhs.split(",").collect();
-        let prop_type = match rhs_fields.len() {
-            1 => PropType::Name,
-            3 => PropType::Vec3,
-            _ => {
-                eprintln!("expression on the right of the = has unrecognized format");
-                panic!();
-            }
-        };
-        let new_name_value = match prop_type {
-            PropType::Name => Some(asset.add_fname(rhs_fields[0])),
-            _ => None,
-        };
-        let new_vec_value = match prop_type {
-            PropType::Vec3 => {
-                let x = rhs_fields[0].parse::<f64>().unwrap();
-                let y = rhs_fields[1].parse::<f64>().unwrap();
-                let z = rhs_fields[2].parse::<f64>().unwrap();
-                Some(Vec3d { x, y, z })
-            }
-            _ => None,
-        };
-
         assert!(
             lhs_fields.len() == 2 || lhs_fields.len() == 3,
             "there must be 2-3 fields in the LHS"
@@ -293,360 +971,2565 @@ fn main() {
             eprintln!("first field of LHS should be the export index");
             panic!();
         };
-
-        let export = asset
-            .get_export_mut(PackageIndex::new(export_index))
-            .unwrap();
-        let export = export.get_normal_export_mut().unwrap();
-        let mut props = &mut export.properties;
-        let mut prop_name = lhs_fields[1];
-        if lhs_fields.len() == 3 {
-            let mut new_props: Option<&mut Vec<Property>> = None;
-            for prop in &mut export.properties {
-                let Property::StructProperty(struct_prop) = prop else {
-                    continue;
-                };
-                if struct_prop.name.get_owned_content() != lhs_fields[1] {
-                    continue;
-                }
-                new_props.replace(&mut struct_prop.value);
-                break;
-            }
-            let Some(v_mut) = new_props else {
-                eprintln!("did not find struct property named '{}'", lhs_fields[1]);
-                panic!();
-            };
-            props = v_mut;
-            prop_name = lhs_fields[2];
-        }
-        let mut found_prop = false;
-        for prop in props {
-            match prop_type {
-                PropType::Name => {
-                    let Property::NameProperty(name_prop) = prop else {
-                        continue;
-                    };
-                    if name_prop.name.get_owned_content() != prop_name {
-                        continue;
-                    }
-                    found_prop = true;
-                    name_prop.value = new_name_value.unwrap();
-                    break;
-                }
-                PropType::Vec3 => match prop {
-                    Property::RotatorProperty(prop) => {
-                        if prop.name.get_owned_content() != prop_name {
-                            continue;
-                        }
-                        found_prop = true;
-                        let v = new_vec_value.unwrap();
-                        prop.value.x.0 = v.x;
-                        prop.value.y.0 = v.y;
-                        prop.value.z.0 = v.z;
-                        break;
-                    }
-                    Property::VectorProperty(prop) => {
-                        if prop.name.get_owned_content() != prop_name {
-                            continue;
-                        }
-                        found_prop = true;
-                        let v = new_vec_value.unwrap();
-                        prop.value.x.0 = v.x;
-                        prop.value.y.0 = v.y;
-                        prop.value.z.0 = v.z;
-                        break;
-                    }
-                    _ => continue,
-                },
-            }
-        }
-        if !found_prop {
-            eprintln!("did not find property named '{}'", prop_name);
-            panic!();
-        }
-        println!(
-            "Edited export: {}: {}.{} = {}",
-            lhs_fields[0],
-            export.get_base_export().object_name.get_owned_content(),
-            &lhs_fields[1..].join("."),
-            rhs
+        edit_export_property(
+            &mut asset,
+            &mut journal,
+            export_index,
+            &lhs_fields[1..],
+            rhs,
         );
     }
 
+    // same as above but the first field of the LHS is a class name, and transform properties
+    // are edited on each actor's root component since that's where they are stored
+    // e.g. BP_Foo_C.RelativeLocation.RelativeLocation=1,2,3
+    for edit_actors in &args.edit_actors_by_class {
+        let Some((lhs, rhs)) = edit_actors.split_once("=") else {
+            panic!();
+        };
+        let lhs_fields: Vec<_> = lhs.split(".").collect();
+        assert!(
+            lhs_fields.len() == 2 || lhs_fields.len() == 3,
+            "there must be 2-3 fields in the LHS"
+        );
+        let actor_indices = find_actors_by_class(&asset, lhs_fields[0]);
+        if actor_indices.is_empty() {
+            eprintln!("Warning: no actors of class '{}' found", lhs_fields[0]);
+        }
+        for actor_index in actor_indices {
+            let export_index = match lhs_fields[1] {
+                "RelativeLocation" | "RelativeRotation" | "RelativeScale3D" => {
+                    let Some(component_index) = find_root_component(&asset, actor_index) else {
+                        eprintln!("actor {} has no root component", actor_index);
+                        panic!();
+                    };
+                    component_index
+                }
+                _ => actor_index,
+            };
+            edit_export_property(
+                &mut asset,
+                &mut journal,
+                export_index,
+                &lhs_fields[1..],
+                rhs,
+            );
+        }
+    }
+
+    // e.g. sphere:0,0,0,1000>0,0,500
+    for move_actors in &args.move_actors_in {
+        let Some((region, offset)) = move_actors.split_once(">") else {
+            eprintln!("move-actors-in expects region>dx,dy,dz");
+            panic!();
+        };
+        let offset = Vec3d::parse(offset);
+        let level_index = find_level_index(&asset, args.level.as_deref());
+        for actor_index in find_actors_in_region(&asset, level_index, &Region::parse(region)) {
+            let (old_location, new_location) = update_actor_transform(
+                &mut asset,
+                &mut journal,
+                actor_index,
+                "RelativeLocation",
+                |v| Vec3d {
+                    x: v.x + offset.x,
+                    y: v.y + offset.y,
+                    z: v.z + offset.z,
+                },
+            )
+            .unwrap();
+            println!(
+                "Moved actor: {}: {} -> {}",
+                actor_index, old_location, new_location
+            );
+        }
+    }
+
+    // e.g. 42=100,0,0
+    // e.g. BP_Crate_C_3+=0,0,50
+    for (edits, name, verb) in [
+        (&args.move_actor, "RelativeLocation", "Moved"),
+        (&args.rotate_actor, "RelativeRotation", "Rotated"),
+        (&args.scale_actor, "RelativeScale3D", "Scaled"),
+    ] {
+        for edit in edits {
+            let Some((actor, value)) = edit.split_once("=") else {
+                eprintln!("'{}' should be actor=x,y,z or actor+=dx,dy,dz", edit);
+                panic!();
+            };
+            let (actor, relative) = match actor.strip_suffix("+") {
+                Some(actor) => (actor, true),
+                None => (actor, false),
+            };
+            let value = Vec3d::parse(value);
+            let actor_indices = match actor.parse::<i32>() {
+                Ok(index) => vec![index],
+                Err(_) => find_actors_by_name(&asset, actor),
+            };
+            if actor_indices.is_empty() {
+                eprintln!("Warning: no actors named '{}' found", actor);
+            }
+            for actor_index in actor_indices {
+                let Some((old, new)) =
+                    update_actor_transform(&mut asset, &mut journal, actor_index, name, |v| {
+                        if relative {
+                            Vec3d {
+                                x: v.x + value.x,
+                                y: v.y + value.y,
+                                z: v.z + value.z,
+                            }
+                        } else {
+                            value
+                        }
+                    })
+                else {
+                    eprintln!("export {} has no root component", actor_index);
+                    panic!();
+                };
+                println!("{} actor: {}: {} -> {}", verb, actor_index, old, new);
+            }
+        }
+    }
+
+    // e.g. 42
+    // e.g. BP_Crate_C_3*10>200,0,0
+    for duplicate_actor in &args.duplicate_actor {
+        let (actor, offset) = match duplicate_actor.split_once(">") {
+            Some((actor, offset)) => (actor, Some(Vec3d::parse(offset))),
+            None => (duplicate_actor.as_str(), None),
+        };
+        let (actor, count) = match actor.split_once("*") {
+            Some((actor, count)) => (actor, count.parse::<i32>().unwrap()),
+            None => (actor, 1),
+        };
+        let actor_indices = match actor.parse::<i32>() {
+            Ok(index) => vec![index],
+            Err(_) => find_actors_by_name(&asset, actor),
+        };
+        if actor_indices.is_empty() {
+            eprintln!("Warning: no actors named '{}' found", actor);
+        }
+        for actor_index in actor_indices {
+            let level_index = asset
+                .get_export(PackageIndex::new(actor_index))
+                .unwrap()
+                .get_base_export()
+                .outer_index;
+            let Some(Export::LevelExport(_)) = asset.get_export(level_index) else {
+                eprintln!("export {} is not an actor in a level", actor_index);
+                panic!();
+            };
+            for i in 1..=count {
+                let copy_index = duplicate_export_subtree(&mut asset, &mut journal, actor_index);
+                journal.record(JournalEntry::Structural {
+                    description: format!("duplicated actor {} as {}", actor_index, copy_index),
+                });
+                if let Export::LevelExport(level) = asset.get_export_mut(level_index).unwrap() {
+                    level.actors.push(PackageIndex::new(copy_index));
+                }
+                rebuild_level_dependencies(&mut asset, level_index);
+                println!(
+                    "Duplicated actor: {} -> {} \"{}\"",
+                    actor_index,
+                    copy_index,
+                    fname_to_string(
+                        &asset
+                            .get_export(PackageIndex::new(copy_index))
+                            .unwrap()
+                            .get_base_export()
+                            .object_name
+                    )
+                );
+                if let Some(offset) = &offset {
+                    let (old, new) = update_actor_transform(
+                        &mut asset,
+                        &mut journal,
+                        copy_index,
+                        "RelativeLocation",
+                        |v| Vec3d {
+                            x: v.x + offset.x * i as f64,
+                            y: v.y + offset.y * i as f64,
+                            z: v.z + offset.z * i as f64,
+                        },
+                    )
+                    .unwrap();
+                    println!("Moved actor: {}: {} -> {}", copy_index, old, new);
+                }
+            }
+        }
+    }
+
+    // actors to delete are selected here but only removed after transplanting, since deleting
+    // renumbers exports and --level and --copy-export take export indices from the input asset
+    let mut actor_indices_to_delete = vec![];
+    for actor in &args.delete_actor_by_name {
+        let actor_indices = find_actors_by_name(&asset, actor);
+        if actor_indices.is_empty() {
+            eprintln!("no actors named '{}' found", actor);
+            panic!();
+        }
+        actor_indices_to_delete.extend(actor_indices);
+    }
+    for i in &args.delete_actor_by_index {
+        let Ok(actor_index) = i.parse::<i32>() else {
+            eprintln!("'{}' should be an export index", i);
+            panic!();
+        };
+        if !is_level_actor(&asset, actor_index) {
+            eprintln!("export {} is not in the actor list of a level", actor_index);
+            panic!();
+        }
+        actor_indices_to_delete.push(actor_index);
+    }
+    for class_name in &args.delete_actor_by_class {
+        actor_indices_to_delete.extend(find_actors_by_class(&asset, class_name));
+    }
+    for region in &args.delete_actors_in {
+        let level_index = find_level_index(&asset, args.level.as_deref());
+        actor_indices_to_delete.extend(find_actors_in_region(
+            &asset,
+            level_index,
+            &Region::parse(region),
+        ));
+    }
+
     if let Some(donor_uasset_path) = args.transplant_donor {
         let donor_uasset_path = Path::new(&donor_uasset_path);
-        let donor_uasset_file = File::open(donor_uasset_path).unwrap();
-        let donor_uexp_path = donor_uasset_path.with_extension("uexp");
-        let donor_uexp_file_maybe = File::open(donor_uexp_path).ok();
-
-        let donor_asset = Asset::new(
-            donor_uasset_file,
-            donor_uexp_file_maybe,
-            unreal_asset::engine_version::EngineVersion::VER_UE5_1,
-            None,
-        )
+        let donor_asset = open_asset(donor_uasset_path).unwrap();
+
+        let level_index = find_level_index(&asset, args.level.as_deref());
+        let mut roots = args.actor_to_transplant.clone();
+        for name in &args.actor_to_transplant_by_name {
+            let actor_indices = find_actors_by_name(&donor_asset, name);
+            if actor_indices.is_empty() {
+                eprintln!("Warning: no actors named '{}' found in donor", name);
+            }
+            roots.extend(actor_indices);
+        }
+        for class_name in &args.actor_to_transplant_by_class {
+            let actor_indices = find_actors_by_class(&donor_asset, class_name);
+            if actor_indices.is_empty() {
+                eprintln!(
+                    "Warning: no actors of class '{}' found in donor",
+                    class_name
+                );
+            }
+            roots.extend(actor_indices);
+        }
+        let mut renames = HashMap::new();
+        for rename in &args.transplant_rename {
+            let Some((old_name, new_name)) = rename.split_once(">") else {
+                eprintln!("transplant-rename expects oldname>newname");
+                panic!();
+            };
+            renames.insert(old_name.to_string(), new_name.to_string());
+        }
+        let location_offset = args.transplant_location_offset.as_deref().map(Vec3d::parse);
+        let rotation_offset = args.transplant_rotation_offset.as_deref().map(Vec3d::parse);
+        let scale = args.transplant_scale.as_deref().map(Vec3d::parse);
+
+        for root_index in &roots {
+            // the donor level that the actor is in gets mapped to the target level
+            let Some(root_export) = donor_asset.get_export(PackageIndex::new(*root_index)) else {
+                eprintln!("donor has no export {}", root_index);
+                panic!();
+            };
+            let donor_level_index = root_export.get_base_export().outer_index;
+            let Some(Export::LevelExport(_)) = donor_asset.get_export(donor_level_index) else {
+                eprintln!(
+                    "donor export {} \"{}\" is not an actor in a level",
+                    root_index,
+                    root_export
+                        .get_base_export()
+                        .object_name
+                        .get_owned_content()
+                );
+                panic!();
+            };
+            let actor_index = transplant_exports(
+                &mut asset,
+                &mut journal,
+                &donor_asset,
+                *root_index,
+                level_index,
+            );
+            journal.record(JournalEntry::Structural {
+                description: format!("transplanted donor actor {} as {}", root_index, actor_index),
+            });
+            if let Export::LevelExport(level) = asset.get_export_mut(level_index).unwrap() {
+                level.actors.push(PackageIndex::new(actor_index));
+            } else {
+                panic!();
+            }
+            rebuild_level_dependencies(&mut asset, level_index);
+
+            let donor_fname = &donor_asset
+                .get_export(PackageIndex::new(*root_index))
+                .unwrap()
+                .get_base_export()
+                .object_name;
+            let donor_name = fname_to_string(donor_fname);
+            // a full name like BP_Crate_C_3 wins over BP_Crate_C, which renames every instance
+            let new_name = renames.get(&donor_name).or_else(|| {
+                renames
+                    .iter()
+                    .find(|(old_name, _)| fname_matches(donor_fname, old_name))
+                    .map(|(_, new_name)| new_name)
+            });
+            if let Some(new_name) = new_name {
+                let new_fname = asset.add_fname(new_name);
+                journal.record(JournalEntry::ExportName {
+                    export: actor_index,
+                    old: JournalName::from(donor_fname),
+                    new: JournalName::from(&new_fname),
+                });
+                asset
+                    .get_export_mut(PackageIndex::new(actor_index))
+                    .unwrap()
+                    .get_base_export_mut()
+                    .object_name = new_fname;
+                println!(
+                    "Renamed transplanted actor: {}: {} -> {}",
+                    actor_index, donor_name, new_name
+                );
+                if let Some(unique_name) =
+                    make_export_name_unique(&mut asset, &mut journal, actor_index)
+                {
+                    println!(
+                        "Renamed transplanted export to avoid collision: {}: {} -> {}",
+                        actor_index, new_name, unique_name
+                    );
+                }
+            }
+            if let Some(offset) = &location_offset {
+                let (old, new) = update_actor_transform(
+                    &mut asset,
+                    &mut journal,
+                    actor_index,
+                    "RelativeLocation",
+                    |v| Vec3d {
+                        x: v.x + offset.x,
+                        y: v.y + offset.y,
+                        z: v.z + offset.z,
+                    },
+                )
+                .unwrap();
+                println!("Moved actor: {}: {} -> {}", actor_index, old, new);
+            }
+            if let Some(offset) = &rotation_offset {
+                let (old, new) = update_actor_transform(
+                    &mut asset,
+                    &mut journal,
+                    actor_index,
+                    "RelativeRotation",
+                    |v| Vec3d {
+                        x: v.x + offset.x,
+                        y: v.y + offset.y,
+                        z: v.z + offset.z,
+                    },
+                )
+                .unwrap();
+                println!("Rotated actor: {}: {} -> {}", actor_index, old, new);
+            }
+            if let Some(scale) = &scale {
+                let (old, new) = update_actor_transform(
+                    &mut asset,
+                    &mut journal,
+                    actor_index,
+                    "RelativeScale3D",
+                    |v| Vec3d {
+                        x: v.x * scale.x,
+                        y: v.y * scale.y,
+                        z: v.z * scale.z,
+                    },
+                )
+                .unwrap();
+                println!("Scaled actor: {}: {} -> {}", actor_index, old, new);
+            }
+        }
+
+        // e.g. 12>0
+        // e.g. DA_Foo_Entry>5
+        for copy_export in &args.copy_export {
+            let Some((export, outer)) = copy_export.split_once(">") else {
+                eprintln!("copy-export expects export>outer");
+                panic!();
+            };
+            let root_index = match export.parse::<i32>() {
+                Ok(index) => index,
+                Err(_) => {
+                    let mut donor_exports = donor_asset.asset_data.exports.iter();
+                    let Some(index) = donor_exports
+                        .position(|e| fname_to_string(&e.get_base_export().object_name) == export)
+                    else {
+                        eprintln!("donor export '{}' not found", export);
+                        panic!();
+                    };
+                    index as i32 + 1
+                }
+            };
+            let Ok(outer_index) = outer.parse::<i32>() else {
+                eprintln!("outer '{}' should be an export index or 0", outer);
+                panic!();
+            };
+            if outer_index < 0
+                || (outer_index != 0 && asset.get_export(PackageIndex::new(outer_index)).is_none())
+            {
+                eprintln!("outer '{}' should be an export index or 0", outer);
+                panic!();
+            }
+            let copy_index = transplant_exports(
+                &mut asset,
+                &mut journal,
+                &donor_asset,
+                root_index,
+                PackageIndex::new(outer_index),
+            );
+            // like a level's actors, the copy has to be created before its outer is serialized
+            if outer_index != 0 {
+                let deps = &mut asset
+                    .get_export_mut(PackageIndex::new(outer_index))
+                    .unwrap()
+                    .get_base_export_mut()
+                    .create_before_serialization_dependencies;
+                if !deps.iter().any(|dep| dep.index == copy_index) {
+                    deps.push(PackageIndex::new(copy_index));
+                }
+            }
+            journal.record(JournalEntry::Structural {
+                description: format!("copied donor export {} as {}", root_index, copy_index),
+            });
+            println!(
+                "Copied export: {} <- {} under {}",
+                copy_index, root_index, outer_index
+            );
+        }
+    }
+
+    // transplanted and copied exports were appended, so the selected indices are still valid
+    if !actor_indices_to_delete.is_empty() {
+        let exports_to_delete = find_export_subtree(&asset, &actor_indices_to_delete);
+        let mut sorted_exports_to_delete: Vec<_> = exports_to_delete.iter().copied().collect();
+        sorted_exports_to_delete.sort();
+        for &index in &sorted_exports_to_delete {
+            println!(
+                "Deleted export: {}: {}",
+                index,
+                asset
+                    .get_export(PackageIndex::new(index))
+                    .unwrap()
+                    .get_base_export()
+                    .object_name
+                    .get_owned_content()
+            );
+        }
+        remove_exports(&mut asset, &exports_to_delete);
+        journal.record(JournalEntry::Structural {
+            description: format!("deleted exports {:?}", sorted_exports_to_delete),
+        });
+    }
+
+    // the same checks as --validate, so edits that would produce a broken asset aren't written
+    let new_errors: Vec<_> = validate::validate(&asset)
+        .into_iter()
+        .filter(|finding| {
+            finding.severity == validate::Severity::Error
+                && !input_errors.contains(&finding.message)
+        })
+        .collect();
+    if !new_errors.is_empty() {
+        for finding in &new_errors {
+            eprintln!("  {}", finding.message);
+        }
+        eprintln!("edits introduced errors that the input didn't have, not writing the asset");
+        panic!();
+    }
+
+    if args.dry_run {
+        println!("Dry run, changes that would be made:");
+        for entry in &journal.entries {
+            println!("  {}", entry);
+        }
+        if journal.entries.is_empty() {
+            println!("  (none)");
+        }
+        return;
+    }
+
+    let mut journal_path = output_uasset_path.with_extension("undo.json");
+    if args.in_place {
+        // levels are .umap rather than .uasset, and the header keeps whichever it had
+        let header_extension = output_uasset_path
+            .extension()
+            .map_or("uasset".to_string(), |extension| {
+                extension.to_string_lossy().to_string()
+            });
+        // written next to the original so the rename below stays on one filesystem
+        let temp_uasset_path =
+            output_uasset_path.with_extension(format!("tmp.{}", header_extension));
+        write_asset(&asset, &temp_uasset_path);
+        let problems = verify_written_asset(&asset, &temp_uasset_path);
+        if !problems.is_empty() {
+            let _ = fs::remove_file(&temp_uasset_path);
+            let _ = fs::remove_file(temp_uasset_path.with_extension("uexp"));
+            for problem in &problems {
+                eprintln!("  {}", problem);
+            }
+            eprintln!(
+                "edited asset does not read back correctly, leaving {} unchanged",
+                output_uasset_path.display()
+            );
+            panic!();
+        }
+        // existing backups and journals are never overwritten, so repeated edits keep the
+        // original around and each backup has the journal of the edit that replaced it
+        let (backup_suffix, journal_extension) = (0..)
+            .map(|n| match n {
+                0 => ("bak".to_string(), "undo.json".to_string()),
+                n => (format!("bak.{}", n), format!("undo.{}.json", n)),
+            })
+            .find(|(backup_suffix, journal_extension)| {
+                [header_extension.as_str(), "uexp"].iter().all(|extension| {
+                    !output_uasset_path
+                        .with_extension(format!("{}.{}", extension, backup_suffix))
+                        .exists()
+                }) && !output_uasset_path
+                    .with_extension(journal_extension)
+                    .exists()
+            })
+            .unwrap();
+        journal_path = output_uasset_path.with_extension(journal_extension);
+        for extension in [header_extension.as_str(), "uexp"] {
+            let path = output_uasset_path.with_extension(extension);
+            if path.exists() {
+                let backup_path =
+                    output_uasset_path.with_extension(format!("{}.{}", extension, backup_suffix));
+                fs::copy(&path, &backup_path).unwrap();
+                println!("Backed up: {}", backup_path.display());
+            }
+            fs::rename(temp_uasset_path.with_extension(extension), &path).unwrap();
+        }
+        println!("Replaced: {}", output_uasset_path.display());
+    } else {
+        write_asset(&asset, output_uasset_path);
+        let problems = verify_written_asset(&asset, output_uasset_path);
+        if !problems.is_empty() {
+            for problem in &problems {
+                eprintln!("  {}", problem);
+            }
+            eprintln!(
+                "{} does not read back correctly and should not be used",
+                output_uasset_path.display()
+            );
+            panic!();
+        }
+    }
+    println!("Verified: {}", output_uasset_path.display());
+
+    journal.save(&journal_path);
+    println!("Wrote undo journal: {}", journal_path.display());
+}
+
+/// Opens a .uasset file along with the .uexp file next to it, if there is one
+fn open_asset(uasset_path: &Path) -> Result<Asset<File>, unreal_asset::Error> {
+    let uasset_file = File::open(uasset_path).unwrap();
+    let uexp_file_maybe = File::open(uasset_path.with_extension("uexp")).ok();
+    Asset::new(
+        uasset_file,
+        uexp_file_maybe,
+        unreal_asset::engine_version::EngineVersion::VER_UE5_1,
+        None,
+    )
+}
+
+/// Writes an asset to a .uasset file and a .uexp file next to it
+fn write_asset(asset: &Asset<File>, uasset_path: &Path) {
+    let mut uasset_file = File::create(uasset_path).unwrap();
+    let mut uexp_file = File::create(uasset_path.with_extension("uexp")).unwrap();
+    asset
+        .write_data(&mut uasset_file, Some(&mut uexp_file))
         .unwrap();
+}
 
-        let persistent_level_index = find_persistent_level_index(&asset).unwrap();
-        let donor_persistent_level_index = find_persistent_level_index(&donor_asset).unwrap();
-        for root_index in &args.actor_to_transplant {
-            let mut exports_to_transplant = vec![];
-            let mut export_map = HashMap::new();
+/// Re-opens a written asset and compares its imports, exports and properties with the asset it
+/// was written from, returning a description of every difference
+fn verify_written_asset(asset: &Asset<File>, uasset_path: &Path) -> Vec<String> {
+    let written = match open_asset(uasset_path) {
+        Ok(written) => written,
+        Err(err) => return vec![format!("cannot parse {}: {}", uasset_path.display(), err)],
+    };
+    let mut problems = vec![];
+    if written.imports.len() != asset.imports.len() {
+        problems.push(format!(
+            "import count: {} written, {} expected",
+            written.imports.len(),
+            asset.imports.len()
+        ));
+    }
+    for (i, (written_import, import)) in written.imports.iter().zip(&asset.imports).enumerate() {
+        let describe = |import: &Import| {
+            format!(
+                "{}.{} {} \"{}\"",
+                import.class_package.get_owned_content(),
+                import.class_name.get_owned_content(),
+                import.outer_index.index,
+                fname_to_string(&import.object_name)
+            )
+        };
+        if describe(written_import) != describe(import) {
+            problems.push(format!(
+                "import {}: {} written, {} expected",
+                -(i as i32 + 1),
+                describe(written_import),
+                describe(import)
+            ));
+        }
+    }
+    let num_exports = asset.asset_data.exports.len();
+    if written.asset_data.exports.len() != num_exports {
+        problems.push(format!(
+            "export count: {} written, {} expected",
+            written.asset_data.exports.len(),
+            num_exports
+        ));
+    }
+    for (i, (written_export, export)) in written
+        .asset_data
+        .exports
+        .iter()
+        .zip(&asset.asset_data.exports)
+        .enumerate()
+    {
+        let index = i as i32 + 1;
+        let (written_base, base) = (written_export.get_base_export(), export.get_base_export());
+        if fname_to_string(&written_base.object_name) != fname_to_string(&base.object_name)
+            || written_base.class_index != base.class_index
+            || written_base.outer_index != base.outer_index
+        {
+            problems.push(format!(
+                "export {}: written as \"{}\" (class {}, outer {}), expected \"{}\" (class {}, outer {})",
+                index,
+                fname_to_string(&written_base.object_name),
+                written_base.class_index.index,
+                written_base.outer_index.index,
+                fname_to_string(&base.object_name),
+                base.class_index.index,
+                base.outer_index.index
+            ));
+        }
+        let package_indices = |export: &Export| {
+            let mut package_indices = vec![];
+            for_each_export_package_index_ref(index, export, &mut |_, field, package_index| {
+                package_indices.push((field.to_string(), package_index.index));
+            });
+            package_indices
+        };
+        let (written_indices, indices) = (package_indices(written_export), package_indices(export));
+        if let Some(((field, written_index), (_, expected_index))) = written_indices
+            .iter()
+            .zip(&indices)
+            .find(|(written, expected)| written != expected)
+        {
+            problems.push(format!(
+                "export {}: {} written as {}, expected {}",
+                index, field, written_index, expected_index
+            ));
+        } else if written_indices.len() != indices.len() {
+            problems.push(format!(
+                "export {}: {} references written, {} expected",
+                index,
+                written_indices.len(),
+                indices.len()
+            ));
+        }
+        let (Some(written_normal), Some(normal)) = (
+            written_export.get_normal_export(),
+            export.get_normal_export(),
+        ) else {
+            continue;
+        };
+        if written_normal.properties.len() != normal.properties.len() {
+            problems.push(format!(
+                "export {}: {} properties written, {} expected",
+                index,
+                written_normal.properties.len(),
+                normal.properties.len()
+            ));
+        }
+        for (written_prop, prop) in written_normal.properties.iter().zip(&normal.properties) {
+            if written_prop != prop {
+                problems.push(format!(
+                    "export {}: property {} differs",
+                    index,
+                    prop.get_name().get_owned_content()
+                ));
+            }
+        }
+    }
+    problems
+}
+
+/// Copies an export from donor_asset into asset along with its subobjects and the exports it
+/// depends on, transplanting or reusing any imports they reference. The copy of root_index is
+/// placed under outer_index. Returns the index of the copy of root_index.
+fn transplant_exports(
+    asset: &mut Asset<File>,
+    journal: &mut Journal,
+    donor_asset: &Asset<File>,
+    root_index: i32,
+    outer_index: PackageIndex,
+) -> i32 {
+    let donor_outer_index = donor_asset
+        .get_export(PackageIndex::new(root_index))
+        .unwrap()
+        .get_base_export()
+        .outer_index;
+    let mut children: HashMap<i32, Vec<i32>> = HashMap::new();
+    for (i, export) in donor_asset.asset_data.exports.iter().enumerate() {
+        children
+            .entry(export.get_base_export().outer_index.index)
+            .or_default()
+            .push(i as i32 + 1);
+    }
+    let mut exports_to_transplant = vec![];
+    let mut donor_indices = vec![];
+    let mut export_map = HashMap::new();
+    {
+        let mut export_stack = vec![root_index];
+        while let Some(cur) = export_stack.pop() {
+            if export_map.contains_key(&cur) {
+                continue;
+            }
+            let cur_exp = donor_asset.get_export(PackageIndex::new(cur)).unwrap();
+            let cur_name = cur_exp.get_base_export().object_name.get_owned_content();
+            if let Some(reason) = unsupported_export_reason(cur_exp) {
+                eprintln!(
+                    "cannot transplant export {} \"{}\": {}",
+                    cur, cur_name, reason
+                );
+                panic!();
+            }
+            // cloning keeps any extra serial data that follows the properties, such as
+            // StaticMeshComponent LOD data
+            exports_to_transplant.push(cur_exp.clone());
+            donor_indices.push(cur);
+            export_map.insert(cur, exports_to_transplant.len() as i32);
+            let base_export = cur_exp.get_base_export();
+            let deps = base_export
+                .serialization_before_serialization_dependencies
+                .iter()
+                .chain(base_export.create_before_serialization_dependencies.iter())
+                .chain(base_export.serialization_before_create_dependencies.iter())
+                .chain(base_export.create_before_create_dependencies.iter());
+            for &index in deps
+                .map(|dep| &dep.index)
+                .chain(children.get(&cur).into_iter().flatten())
             {
-                let mut export_stack = vec![*root_index];
-                while let Some(cur) = export_stack.pop() {
-                    let cur_exp = donor_asset.get_export(PackageIndex::new(cur)).unwrap();
-                    exports_to_transplant.push(cur_exp.clone());
-                    export_map.insert(cur, exports_to_transplant.len() as i32);
-                    for dep in &cur_exp
-                        .get_base_export()
-                        .create_before_serialization_dependencies
-                    {
-                        if dep.index < 1 {
-                            continue;
-                        }
-                        if export_map.contains_key(&dep.index) {
-                            continue;
-                        }
-                        export_stack.push(dep.index);
+                if index < 1 || index == donor_outer_index.index {
+                    continue;
+                }
+                if export_map.contains_key(&index) {
+                    continue;
+                }
+                export_stack.push(index);
+            }
+        }
+    }
+    // outer chains of these imports are followed when they are transplanted
+    let mut donor_import_indices = vec![];
+    for (export, &owner) in exports_to_transplant.iter().zip(&donor_indices) {
+        let mut references = vec![];
+        for_each_export_package_index_ref(owner, export, &mut |_, _, package_index| {
+            references.push(package_index)
+        });
+        for reference in references {
+            if reference.index >= 0 {
+                continue;
+            }
+            if donor_import_indices.contains(&reference.index) {
+                continue;
+            }
+            donor_import_indices.push(reference.index);
+        }
+    }
+
+    let mut export_tuples: Vec<(i32, i32)> = export_map
+        .iter()
+        .map(|(&k, &v)| (k, v + asset.asset_data.exports.len() as i32))
+        .collect();
+
+    export_tuples.sort_by_key(|&(_, dst)| dst);
+
+    for &(src, dst) in &export_tuples {
+        let name = donor_asset
+            .get_export(PackageIndex::new(src))
+            .unwrap()
+            .get_base_export()
+            .object_name
+            .get_owned_content();
+        println!("Transplanting export: {} <- {} \"{}\"", dst, src, name);
+    }
+    let mut import_map = HashMap::new();
+    for donor_import_index in donor_import_indices {
+        transplant_import(
+            asset,
+            donor_asset,
+            PackageIndex::new(donor_import_index),
+            &mut import_map,
+        );
+    }
+
+    let export_map: HashMap<i32, i32> = export_tuples.into_iter().collect();
+
+    let mut expected_combined_size = export_map.len() + import_map.len();
+    let mut combined_map = HashMap::new();
+    combined_map.extend(export_map);
+    combined_map.extend(import_map);
+    // references to the donor outer (usually its level) are redirected to the new outer
+    if donor_outer_index.index > 0 {
+        combined_map.insert(donor_outer_index.index, outer_index.index);
+        expected_combined_size += 1;
+    }
+    assert_eq!(expected_combined_size, combined_map.len());
+
+    // any reference the maps can't resolve would point at an unrelated object in the target
+    let mut remap = |owner: i32, field: &str, package_index: &mut PackageIndex| {
+        if package_index.index == 0 {
+            return;
+        }
+        let Some(&index) = combined_map.get(&package_index.index) else {
+            eprintln!(
+                "cannot transplant export {}: {} references {} which was not transplanted",
+                owner, field, package_index.index
+            );
+            panic!();
+        };
+        package_index.index = index;
+    };
+    for (export, &owner) in exports_to_transplant.iter_mut().zip(&donor_indices) {
+        let base_export = export.get_base_export_mut();
+        base_export.object_name = rehome_fname(asset, &base_export.object_name);
+        for_each_export_package_index(owner, export, &mut remap);
+        // the donor outer maps to 0 when the copy has no outer, which isn't a dependency
+        remove_null_export_dependencies(export);
+        for_each_export_prop(export, &mut |prop| rehome_property_fnames(asset, prop));
+        if let Export::EnumExport(enum_export) = export {
+            for (name, _) in &mut enum_export.value.names {
+                *name = rehome_fname(asset, name);
+            }
+        }
+    }
+
+    // the root is always the first export gathered
+    exports_to_transplant[0].get_base_export_mut().outer_index = outer_index;
+    asset
+        .asset_data
+        .exports
+        .extend_from_slice(&exports_to_transplant);
+
+    // exports are referenced by index, so renaming them doesn't break any references
+    let num_exports = asset.asset_data.exports.len() as i32;
+    let first_transplanted_index = num_exports - exports_to_transplant.len() as i32 + 1;
+    for index in first_transplanted_index..=num_exports {
+        let old_name = fname_to_string(
+            &asset
+                .get_export(PackageIndex::new(index))
+                .unwrap()
+                .get_base_export()
+                .object_name,
+        );
+        if let Some(new_name) = make_export_name_unique(asset, journal, index) {
+            println!(
+                "Renamed transplanted export to avoid collision: {}: {} -> {}",
+                index, old_name, new_name
+            );
+        }
+    }
+    *combined_map.get(&root_index).unwrap()
+}
+
+/// Sets a name or vector property on an export (prop_path is propname or structname.propname)
+fn edit_export_property(
+    asset: &mut Asset<File>,
+    journal: &mut Journal,
+    export_index: i32,
+    prop_path: &[&str],
+    rhs: &str,
+) {
+    let rhs_fields: Vec<_> = rhs.split(",").collect();
+    let prop_type = match rhs_fields.len() {
+        1 => PropType::Name,
+        3 => PropType::Vec3,
+        _ => {
+            eprintln!("expression on the right of the = has unrecognized format");
+            panic!();
+        }
+    };
+    let new_name_value = match prop_type {
+        PropType::Name => Some(asset.add_fname(rhs_fields[0])),
+        _ => None,
+    };
+    let new_vec_value = match prop_type {
+        PropType::Vec3 => {
+            let x = rhs_fields[0].parse::<f64>().unwrap();
+            let y = rhs_fields[1].parse::<f64>().unwrap();
+            let z = rhs_fields[2].parse::<f64>().unwrap();
+            Some(Vec3d { x, y, z })
+        }
+        _ => None,
+    };
+
+    let export = asset
+        .get_export_mut(PackageIndex::new(export_index))
+        .unwrap();
+    let export = export.get_normal_export_mut().unwrap();
+    let mut props = &mut export.properties;
+    let mut prop_name = prop_path[0];
+    if prop_path.len() == 2 {
+        let mut new_props: Option<&mut Vec<Property>> = None;
+        for prop in &mut export.properties {
+            let Property::StructProperty(struct_prop) = prop else {
+                continue;
+            };
+            if struct_prop.name.get_owned_content() != prop_path[0] {
+                continue;
+            }
+            new_props.replace(&mut struct_prop.value);
+            break;
+        }
+        let Some(v_mut) = new_props else {
+            eprintln!("did not find struct property named '{}'", prop_path[0]);
+            panic!();
+        };
+        props = v_mut;
+        prop_name = prop_path[1];
+    }
+    let mut found_prop = false;
+    for prop in props {
+        match prop_type {
+            PropType::Name => {
+                let Property::NameProperty(name_prop) = prop else {
+                    continue;
+                };
+                if name_prop.name.get_owned_content() != prop_name {
+                    continue;
+                }
+                found_prop = true;
+                let new_value = new_name_value.unwrap();
+                journal.record(JournalEntry::Property {
+                    export: export_index,
+                    path: prop_path.iter().map(|s| s.to_string()).collect(),
+                    old: JournalValue::Name(JournalName::from(&name_prop.value)),
+                    new: JournalValue::Name(JournalName::from(&new_value)),
+                });
+                name_prop.value = new_value;
+                break;
+            }
+            PropType::Vec3 => match prop {
+                Property::RotatorProperty(prop) => {
+                    if prop.name.get_owned_content() != prop_name {
+                        continue;
+                    }
+                    found_prop = true;
+                    let v = new_vec_value.unwrap();
+                    journal.record(JournalEntry::Property {
+                        export: export_index,
+                        path: prop_path.iter().map(|s| s.to_string()).collect(),
+                        old: JournalValue::Vector(Vec3d {
+                            x: prop.value.x.0,
+                            y: prop.value.y.0,
+                            z: prop.value.z.0,
+                        }),
+                        new: JournalValue::Vector(v),
+                    });
+                    prop.value.x.0 = v.x;
+                    prop.value.y.0 = v.y;
+                    prop.value.z.0 = v.z;
+                    break;
+                }
+                Property::VectorProperty(prop) => {
+                    if prop.name.get_owned_content() != prop_name {
+                        continue;
+                    }
+                    found_prop = true;
+                    let v = new_vec_value.unwrap();
+                    journal.record(JournalEntry::Property {
+                        export: export_index,
+                        path: prop_path.iter().map(|s| s.to_string()).collect(),
+                        old: JournalValue::Vector(Vec3d {
+                            x: prop.value.x.0,
+                            y: prop.value.y.0,
+                            z: prop.value.z.0,
+                        }),
+                        new: JournalValue::Vector(v),
+                    });
+                    prop.value.x.0 = v.x;
+                    prop.value.y.0 = v.y;
+                    prop.value.z.0 = v.z;
+                    break;
+                }
+                _ => continue,
+            },
+        }
+    }
+    if !found_prop {
+        eprintln!("did not find property named '{}'", prop_name);
+        panic!();
+    }
+    println!(
+        "Edited export: {}: {}.{} = {}",
+        export_index,
+        export.get_base_export().object_name.get_owned_content(),
+        prop_path.join("."),
+        rhs
+    );
+}
+
+/// Returns why an export can't be copied into another asset, if it can't
+fn unsupported_export_reason(export: &Export) -> Option<&'static str> {
+    match export {
+        Export::ClassExport(_)
+        | Export::StructExport(_)
+        | Export::FunctionExport(_)
+        | Export::UserDefinedStructExport(_) => {
+            Some("Blueprint bytecode and field definitions can't be remapped")
+        }
+        Export::PropertyExport(_) => Some("Blueprint property definitions can't be remapped"),
+        Export::LevelExport(_) => Some("levels can't be copied into other assets"),
+        Export::RawExport(_) => {
+            Some("unparsed data may hold names and indices that can't be remapped")
+        }
+        _ => None,
+    }
+}
+
+/// Returns the level named or indexed by level, or PersistentLevel if level is None, or the only
+/// level in the asset if it has no PersistentLevel
+fn find_level_index(asset: &Asset<File>, level: Option<&str>) -> PackageIndex {
+    let mut levels = vec![];
+    for (i, export) in asset.asset_data.exports.iter().enumerate() {
+        let Export::LevelExport(export) = export else {
+            continue;
+        };
+        levels.push((
+            i as i32 + 1,
+            export.get_base_export().object_name.get_owned_content(),
+        ));
+    }
+    let found = match level {
+        Some(level) => levels
+            .iter()
+            .find(|(index, name)| name == level || index.to_string() == level)
+            .map(|&(index, _)| PackageIndex::new(index)),
+        None => find_persistent_level_index(asset).or(match levels.as_slice() {
+            [(index, _)] => Some(PackageIndex::new(*index)),
+            _ => None,
+        }),
+    };
+    if let Some(level_index) = found {
+        return level_index;
+    }
+    match level {
+        Some(level) => eprintln!("level '{}' not found", level),
+        None => eprintln!("no PersistentLevel found; use --level to choose a level"),
+    }
+    if levels.is_empty() {
+        eprintln!("asset has no levels");
+    }
+    for (index, name) in &levels {
+        eprintln!("  level {}: {}", index, name);
+    }
+    panic!();
+}
+
+fn find_persistent_level_index(asset: &Asset<File>) -> Option<PackageIndex> {
+    for (i, export) in asset.asset_data.exports.iter().enumerate() {
+        let Export::LevelExport(export) = export else {
+            continue;
+        };
+        if export.get_base_export().object_name.get_owned_content() != "PersistentLevel" {
+            continue;
+        }
+        return Some(PackageIndex::new(i as i32 + 1));
+    }
+    None
+}
+
+/// Returns the name of a class followed by the names of its parent classes, as far as they can be
+/// resolved within the asset
+fn get_class_chain(asset: &Asset<File>, class_index: PackageIndex) -> Vec<String> {
+    let mut chain = vec![];
+    let mut cur = class_index;
+    // the length check guards against super_index cycles in malformed assets
+    while cur.index != 0 && chain.len() < 64 {
+        if cur.index < 0 {
+            if let Some(import) = asset.get_import(cur) {
+                chain.push(import.object_name.get_owned_content());
+            }
+            break;
+        }
+        let Some(export) = asset.get_export(cur) else {
+            break;
+        };
+        chain.push(export.get_base_export().object_name.get_owned_content());
+        cur = export.get_base_export().super_index;
+    }
+    chain
+}
+
+/// Describes every actor in every level, followed by exports directly under a level that look
+/// like actors but are missing from its actor list
+fn list_actors(asset: &Asset<File>) -> Vec<ActorReport> {
+    let mut reports = vec![];
+    for (i, export) in asset.asset_data.exports.iter().enumerate() {
+        let Export::LevelExport(level) = export else {
+            continue;
+        };
+        let level_index = i as i32 + 1;
+        let mut actor_indices: Vec<(i32, bool)> = level
+            .actors
+            .iter()
+            .filter(|actor| actor.index > 0)
+            .map(|actor| (actor.index, true))
+            .collect();
+        // the model and its components live under the level without being actors
+        let mut held: HashSet<i32> = level.actors.iter().map(|actor| actor.index).collect();
+        held.extend(
+            level
+                .model_components
+                .iter()
+                .map(|component| component.index),
+        );
+        held.extend([level.model.index, level.level_script.index]);
+        for (j, export) in asset.asset_data.exports.iter().enumerate() {
+            let index = j as i32 + 1;
+            if export.get_base_export().outer_index.index != level_index || held.contains(&index) {
+                continue;
+            }
+            if find_root_component(asset, index).is_some() {
+                actor_indices.push((index, false));
+            }
+        }
+        for (index, in_actors_list) in actor_indices {
+            let Some(actor_export) = asset.get_export(PackageIndex::new(index)) else {
+                continue;
+            };
+            let base_export = actor_export.get_base_export();
+            let root_props = find_root_component(asset, index)
+                .and_then(|component| asset.get_export(PackageIndex::new(component)))
+                .and_then(|component| component.get_normal_export())
+                .map(|component| &component.properties);
+            reports.push(ActorReport {
+                level: level_index,
+                index,
+                name: fname_to_string(&base_export.object_name),
+                class: get_class_chain(asset, base_export.class_index)
+                    .first()
+                    .cloned()
+                    .unwrap_or_default(),
+                location: root_props
+                    .map(|props| get_struct_vector(props, "RelativeLocation").unwrap_or_default()),
+                rotation: root_props
+                    .map(|props| get_struct_vector(props, "RelativeRotation").unwrap_or_default()),
+                components: find_export_subtree(asset, &[index]).len() - 1,
+                in_actors_list,
+            });
+        }
+    }
+    reports
+}
+
+/// Returns the export index of every level actor named name, with or without its number
+fn find_actors_by_name(asset: &Asset<File>, name: &str) -> Vec<i32> {
+    let mut actor_indices = vec![];
+    for export in &asset.asset_data.exports {
+        let Export::LevelExport(level) = export else {
+            continue;
+        };
+        for actor in &level.actors {
+            if actor.index <= 0 {
+                continue;
+            }
+            let Some(actor_export) = asset.get_export(*actor) else {
+                continue;
+            };
+            if fname_matches(&actor_export.get_base_export().object_name, name) {
+                actor_indices.push(actor.index);
+            }
+        }
+    }
+    actor_indices
+}
+
+/// Returns whether an export is in the actor list of any level
+fn is_level_actor(asset: &Asset<File>, export_index: i32) -> bool {
+    asset.asset_data.exports.iter().any(|export| match export {
+        Export::LevelExport(level) => level.actors.iter().any(|actor| actor.index == export_index),
+        _ => false,
+    })
+}
+
+/// Returns the export index of every level actor whose class or parent class is named class_name
+fn find_actors_by_class(asset: &Asset<File>, class_name: &str) -> Vec<i32> {
+    let mut actor_indices = vec![];
+    for export in &asset.asset_data.exports {
+        let Export::LevelExport(level) = export else {
+            continue;
+        };
+        for actor in &level.actors {
+            if actor.index <= 0 {
+                continue;
+            }
+            let Some(actor_export) = asset.get_export(*actor) else {
+                continue;
+            };
+            let class_chain = get_class_chain(asset, actor_export.get_base_export().class_index);
+            if class_chain.iter().any(|name| name == class_name) {
+                actor_indices.push(actor.index);
+            }
+        }
+    }
+    actor_indices
+}
+
+/// Returns the export index of the component referenced by an actor's RootComponent property
+fn find_root_component(asset: &Asset<File>, actor_index: i32) -> Option<i32> {
+    let normal_export = asset
+        .get_export(PackageIndex::new(actor_index))?
+        .get_normal_export()?;
+    for prop in &normal_export.properties {
+        let Property::ObjectProperty(prop) = prop else {
+            continue;
+        };
+        if prop.name.get_owned_content() == "RootComponent" && prop.value.index > 0 {
+            return Some(prop.value.index);
+        }
+    }
+    None
+}
+
+/// Returns the location of an actor's root component, which is the origin if the component has
+/// no RelativeLocation property
+fn find_actor_location(asset: &Asset<File>, actor_index: i32) -> Option<Vec3d> {
+    let component_index = find_root_component(asset, actor_index)?;
+    let normal_export = asset
+        .get_export(PackageIndex::new(component_index))?
+        .get_normal_export()?;
+    Some(get_struct_vector(&normal_export.properties, "RelativeLocation").unwrap_or_default())
+}
+
+/// Returns the export index of every actor of a level whose root component is inside region
+fn find_actors_in_region(
+    asset: &Asset<File>,
+    level_index: PackageIndex,
+    region: &Region,
+) -> Vec<i32> {
+    let mut actor_indices = vec![];
+    for actor in level_snapshot(asset, level_index).0 {
+        if actor <= 0 {
+            continue;
+        }
+        let Some(location) = find_actor_location(asset, actor) else {
+            continue;
+        };
+        if region.contains(&location) {
+            actor_indices.push(actor);
+        }
+    }
+    actor_indices
+}
+
+/// Reads the vector or rotator inside a struct property such as RelativeLocation
+fn get_struct_vector(props: &[Property], name: &str) -> Option<Vec3d> {
+    for prop in props {
+        let Property::StructProperty(struct_prop) = prop else {
+            continue;
+        };
+        if struct_prop.name.get_owned_content() != name {
+            continue;
+        }
+        for prop in &struct_prop.value {
+            match prop {
+                Property::VectorProperty(prop) => {
+                    return Some(Vec3d {
+                        x: prop.value.x.0,
+                        y: prop.value.y.0,
+                        z: prop.value.z.0,
+                    })
+                }
+                Property::RotatorProperty(prop) => {
+                    return Some(Vec3d {
+                        x: prop.value.x.0,
+                        y: prop.value.y.0,
+                        z: prop.value.z.0,
+                    })
+                }
+                _ => (),
+            }
+        }
+    }
+    None
+}
+
+/// Writes the vector or rotator inside a struct property such as RelativeLocation, returning
+/// false if there is no such property
+fn set_struct_vector(props: &mut [Property], name: &str, v: &Vec3d) -> bool {
+    for prop in props {
+        let Property::StructProperty(struct_prop) = prop else {
+            continue;
+        };
+        if struct_prop.name.get_owned_content() != name {
+            continue;
+        }
+        for prop in &mut struct_prop.value {
+            match prop {
+                Property::VectorProperty(prop) => {
+                    prop.value.x.0 = v.x;
+                    prop.value.y.0 = v.y;
+                    prop.value.z.0 = v.z;
+                    return true;
+                }
+                Property::RotatorProperty(prop) => {
+                    prop.value.x.0 = v.x;
+                    prop.value.y.0 = v.y;
+                    prop.value.z.0 = v.z;
+                    return true;
+                }
+                _ => (),
+            }
+        }
+    }
+    false
+}
+
+/// Builds a struct property such as RelativeLocation that wraps a single vector or rotator
+fn new_struct_vector(
+    asset: &mut Asset<File>,
+    name: &str,
+    struct_type: &str,
+    v: &Vec3d,
+) -> Property {
+    let name = asset.add_fname(name);
+    let value = Vector::new(v.x.into(), v.y.into(), v.z.into());
+    let inner = match struct_type {
+        "Rotator" => Property::RotatorProperty(RotatorProperty {
+            name: name.clone(),
+            ancestry: Default::default(),
+            property_guid: None,
+            duplication_index: 0,
+            value,
+        }),
+        _ => Property::VectorProperty(VectorProperty {
+            name: name.clone(),
+            ancestry: Default::default(),
+            property_guid: None,
+            duplication_index: 0,
+            value,
+        }),
+    };
+    Property::StructProperty(StructProperty {
+        name,
+        ancestry: Default::default(),
+        struct_type: Some(asset.add_fname(struct_type)),
+        struct_guid: None,
+        property_guid: None,
+        duplication_index: 0,
+        serialize_none: true,
+        value: vec![inner],
+    })
+}
+
+/// Updates RelativeLocation, RelativeRotation or RelativeScale3D on an actor's root component,
+/// adding the property if the component doesn't have it yet. Returns the old and new values, or
+/// None if the actor has no root component.
+fn update_actor_transform<F>(
+    asset: &mut Asset<File>,
+    journal: &mut Journal,
+    actor_index: i32,
+    name: &str,
+    f: F,
+) -> Option<(Vec3d, Vec3d)>
+where
+    F: FnOnce(&Vec3d) -> Vec3d,
+{
+    let component_index = PackageIndex::new(find_root_component(asset, actor_index)?);
+    let props = &mut asset
+        .get_export_mut(component_index)?
+        .get_normal_export_mut()?
+        .properties;
+    let existing_value = get_struct_vector(props, name);
+    let old_value = match existing_value {
+        Some(v) => v,
+        // components without the property use the engine defaults
+        None if name == "RelativeScale3D" => Vec3d {
+            x: 1.0,
+            y: 1.0,
+            z: 1.0,
+        },
+        None => Vec3d::default(),
+    };
+    let new_value = f(&old_value);
+    if !set_struct_vector(props, name, &new_value) {
+        let struct_type = match name {
+            "RelativeRotation" => "Rotator",
+            _ => "Vector",
+        };
+        let prop = new_struct_vector(asset, name, struct_type, &new_value);
+        asset
+            .get_export_mut(component_index)?
+            .get_normal_export_mut()?
+            .properties
+            .push(prop);
+    }
+    journal.record(JournalEntry::Transform {
+        component: component_index.index,
+        property: name.to_string(),
+        old: existing_value,
+        new: new_value,
+    });
+    Some((old_value, new_value))
+}
+
+/// Formats an FName the way the engine displays it, with its number as a suffix
+fn fname_to_string(fname: &FName) -> String {
+    match fname.get_number() {
+        0 => fname.get_owned_content(),
+        number => format!("{}_{}", fname.get_owned_content(), number - 1),
+    }
+}
+
+/// Returns whether name is an FName's full name, or its name without the number, which matches
+/// every numbered instance
+fn fname_matches(fname: &FName, name: &str) -> bool {
+    fname.get_owned_content() == name || fname_to_string(fname) == name
+}
+
+/// Returns the lowest FName number that isn't used by a sibling with the same name, or None if
+/// number is already free
+fn free_name_number(number: i32, used_numbers: &HashSet<i32>) -> Option<i32> {
+    if !used_numbers.contains(&number) {
+        return None;
+    }
+    // numbered names start at 1, which the engine displays as name_0
+    let mut number = number.max(1);
+    while used_numbers.contains(&number) {
+        number += 1;
+    }
+    Some(number)
+}
+
+/// Gives an export a new FName number if another export with the same outer has the same name,
+/// returning the new name if it was changed
+fn make_export_name_unique(
+    asset: &mut Asset<File>,
+    journal: &mut Journal,
+    export_index: i32,
+) -> Option<String> {
+    let base_export = asset
+        .get_export(PackageIndex::new(export_index))
+        .unwrap()
+        .get_base_export();
+    let outer_index = base_export.outer_index.index;
+    let name = base_export.object_name.get_owned_content();
+    let number = base_export.object_name.get_number();
+    let mut used_numbers = HashSet::new();
+    for (i, export) in asset.asset_data.exports.iter().enumerate() {
+        if i as i32 + 1 == export_index {
+            continue;
+        }
+        let base_export = export.get_base_export();
+        if base_export.outer_index.index != outer_index {
+            continue;
+        }
+        if base_export.object_name.get_owned_content() != name {
+            continue;
+        }
+        used_numbers.insert(base_export.object_name.get_number());
+    }
+    let number = free_name_number(number, &used_numbers)?;
+    let new_fname = asset.add_fname_with_number(&name, number);
+    let new_name = fname_to_string(&new_fname);
+    let object_name = &mut asset
+        .get_export_mut(PackageIndex::new(export_index))
+        .unwrap()
+        .get_base_export_mut()
+        .object_name;
+    journal.record(JournalEntry::ExportName {
+        export: export_index,
+        old: JournalName::from(&*object_name),
+        new: JournalName::from(&new_fname),
+    });
+    *object_name = new_fname;
+    Some(new_name)
+}
+
+/// Finds an import by path. Names are compared in full, so SM_Foo_1 doesn't match SM_Foo.
+fn find_import(
+    asset: &Asset<File>,
+    class_package: &str,
+    class_name: &str,
+    outer_index: PackageIndex,
+    object_name: &str,
+) -> Option<PackageIndex> {
+    for (i, import) in asset.imports.iter().enumerate() {
+        if import.outer_index.index != outer_index.index {
+            continue;
+        }
+        if fname_to_string(&import.object_name) != object_name {
+            continue;
+        }
+        if fname_to_string(&import.class_name) != class_name {
+            continue;
+        }
+        if fname_to_string(&import.class_package) != class_package {
+            continue;
+        }
+        return Some(PackageIndex::new(-(i as i32 + 1)));
+    }
+    None
+}
+
+/// Accepts either an import's object name or its negative index
+fn find_import_by_name(asset: &Asset<File>, name: &str) -> Option<PackageIndex> {
+    if let Ok(index) = name.parse::<i32>() {
+        if index < 0 && asset.get_import(PackageIndex::new(index)).is_some() {
+            return Some(PackageIndex::new(index));
+        }
+        return None;
+    }
+    for (i, import) in asset.imports.iter().enumerate() {
+        if fname_to_string(&import.object_name) == name {
+            return Some(PackageIndex::new(-(i as i32 + 1)));
+        }
+    }
+    None
+}
+
+/// Guesses the outer package of an import whose outer was zeroed, preferring the package of the
+/// same asset (/Game/Foo/BP_Foo for BP_Foo_C) and then the import's class package. The class
+/// package of a Class import is where UClass lives, never its outer, so it isn't guessed.
+fn infer_import_outer(asset: &Asset<File>, import_index: PackageIndex) -> Option<PackageIndex> {
+    let import = asset.get_import(import_index)?;
+    let object_name = import.object_name.get_owned_content();
+    let asset_name = object_name
+        .strip_prefix("Default__")
+        .unwrap_or(&object_name);
+    let asset_name = asset_name.strip_suffix("_C").unwrap_or(asset_name);
+    let class_package = import.class_package.get_owned_content();
+    let packages: Vec<(i32, String)> = asset
+        .imports
+        .iter()
+        .enumerate()
+        .filter(|(_, import)| import.class_name.get_owned_content() == "Package")
+        .map(|(i, import)| (-(i as i32 + 1), import.object_name.get_owned_content()))
+        .filter(|(index, _)| *index != import_index.index)
+        .collect();
+    let is_class = import.class_name.get_owned_content() == "Class";
+    let suffix = format!("/{}", asset_name);
+    packages
+        .iter()
+        .find(|(_, name)| name.ends_with(&suffix))
+        .or_else(|| {
+            packages
+                .iter()
+                .find(|(_, name)| !is_class && *name == class_package)
+        })
+        .map(|(index, _)| PackageIndex::new(*index))
+}
+
+/// Deletes an import and renumbers every import index that came after it. The import must no
+/// longer be referenced.
+fn remove_import_at(asset: &mut Asset<File>, index: PackageIndex) {
+    check_indices_can_be_renumbered(asset, "remove an import");
+    asset.imports.remove((-index.index - 1) as usize);
+    for_each_package_index(asset, &mut |_, _, package_index| {
+        if package_index.index < index.index {
+            package_index.index += 1;
+        }
+    });
+}
+
+/// Returns the given exports plus every export that has one of them in its outer chain
+fn find_export_subtree(asset: &Asset<File>, roots: &[i32]) -> HashSet<i32> {
+    let mut subtree: HashSet<i32> = roots.iter().copied().collect();
+    loop {
+        let mut found_new = false;
+        for (i, export) in asset.asset_data.exports.iter().enumerate() {
+            let index = i as i32 + 1;
+            if subtree.contains(&index) {
+                continue;
+            }
+            if subtree.contains(&export.get_base_export().outer_index.index) {
+                subtree.insert(index);
+                found_new = true;
+            }
+        }
+        if !found_new {
+            break;
+        }
+    }
+    subtree
+}
+
+/// Appends a copy of an export and its subtree, pointing references within the subtree at the
+/// copies and giving copies unique names. Returns the index of the copy of root_index.
+fn duplicate_export_subtree(
+    asset: &mut Asset<File>,
+    journal: &mut Journal,
+    root_index: i32,
+) -> i32 {
+    let mut subtree: Vec<i32> = find_export_subtree(asset, &[root_index])
+        .into_iter()
+        .collect();
+    subtree.sort();
+    let first_copy_index = asset.asset_data.exports.len() as i32 + 1;
+    let copy_map: HashMap<i32, i32> = subtree
+        .iter()
+        .enumerate()
+        .map(|(i, &index)| (index, first_copy_index + i as i32))
+        .collect();
+
+    let mut copies = vec![];
+    for index in &subtree {
+        let mut export = asset.get_export(PackageIndex::new(*index)).unwrap().clone();
+        for_each_export_package_index(*index, &mut export, &mut |_, _, package_index| {
+            package_index.index = *copy_map
+                .get(&package_index.index)
+                .unwrap_or(&package_index.index);
+        });
+        copies.push(export);
+    }
+    asset.asset_data.exports.extend(copies);
+
+    for index in first_copy_index..first_copy_index + subtree.len() as i32 {
+        make_export_name_unique(asset, journal, index);
+    }
+    *copy_map.get(&root_index).unwrap()
+}
+
+/// Deletes a set of exports, dropping them from dependency and actor lists, nulling any other
+/// references to them and renumbering every export index that comes after them
+fn remove_exports(asset: &mut Asset<File>, removed: &HashSet<i32>) {
+    check_indices_can_be_renumbered(asset, "delete exports");
+    for export in &mut asset.asset_data.exports {
+        let base_export = export.get_base_export_mut();
+        for deps in [
+            &mut base_export.serialization_before_serialization_dependencies,
+            &mut base_export.create_before_serialization_dependencies,
+            &mut base_export.serialization_before_create_dependencies,
+            &mut base_export.create_before_create_dependencies,
+        ] {
+            deps.retain(|dep| !removed.contains(&dep.index));
+        }
+        if let Export::LevelExport(level) = export {
+            level.actors.retain(|actor| !removed.contains(&actor.index));
+            level
+                .model_components
+                .retain(|model_component| !removed.contains(&model_component.index));
+        }
+        if let Some(struct_export) = get_struct_export_mut(export) {
+            struct_export
+                .children
+                .retain(|child| !removed.contains(&child.index));
+        }
+    }
+
+    let mut new_indices = HashMap::new();
+    let mut next_index = 1;
+    for index in 1..=(asset.asset_data.exports.len() as i32) {
+        if removed.contains(&index) {
+            continue;
+        }
+        new_indices.insert(index, next_index);
+        next_index += 1;
+    }
+    for_each_package_index(asset, &mut |owner, field, package_index| {
+        if package_index.index <= 0 {
+            return;
+        }
+        let Some(&new_index) = new_indices.get(&package_index.index) else {
+            if !removed.contains(&owner) {
+                eprintln!(
+                    "Warning: nulled reference to deleted export {} in {}: {}",
+                    package_index.index, owner, field
+                );
+            }
+            package_index.index = 0;
+            return;
+        };
+        package_index.index = new_index;
+    });
+
+    let mut index = 0;
+    asset.asset_data.exports.retain(|_| {
+        index += 1;
+        !removed.contains(&index)
+    });
+}
+
+/// Returns a level's actor list and preload dependencies as they are now, for the undo journal
+fn level_snapshot(asset: &Asset<File>, level_index: PackageIndex) -> (Vec<i32>, Vec<i32>) {
+    let Some(Export::LevelExport(level)) = asset.get_export(level_index) else {
+        eprintln!("export {} is not a level", level_index.index);
+        panic!();
+    };
+    (
+        level.actors.iter().map(|actor| actor.index).collect(),
+        level
+            .get_base_export()
+            .create_before_serialization_dependencies
+            .iter()
+            .map(|dep| dep.index)
+            .collect(),
+    )
+}
+
+/// Reverts the changes recorded in an undo journal, newest first. Every value must still be what
+/// the journal changed it to, so the journal has to be applied to the asset it was written with.
+fn undo_journal(asset: &mut Asset<File>, journal: &Journal) {
+    let structural: Vec<_> = journal
+        .entries
+        .iter()
+        .filter_map(|entry| match entry {
+            JournalEntry::Structural { description } => Some(description),
+            _ => None,
+        })
+        .collect();
+    if !structural.is_empty() {
+        for description in structural {
+            eprintln!("  {}", description);
+        }
+        eprintln!("undo journal contains changes that renumber imports or exports and can't be undone; re-run from the original asset instead");
+        panic!();
+    }
+    let mismatch = |entry: &JournalEntry| -> ! {
+        eprintln!("asset does not match undo journal entry {:?}", entry);
+        panic!();
+    };
+    for entry in journal.entries.iter().rev() {
+        match entry {
+            JournalEntry::ImportOuter { import, old, new } => {
+                let Some(import_ref) = asset.imports.get_mut((-import - 1) as usize) else {
+                    mismatch(entry);
+                };
+                if import_ref.outer_index.index != *new {
+                    mismatch(entry);
+                }
+                import_ref.outer_index.index = *old;
+                println!("Updated import: {}: {} -> {}", import, new, old);
+            }
+            JournalEntry::ImportName { import, old, new } => {
+                let old_fname = asset.add_fname_with_number(&old.name, old.number);
+                let Some(import_ref) = asset.imports.get_mut((-import - 1) as usize) else {
+                    mismatch(entry);
+                };
+                if JournalName::from(&import_ref.object_name) != *new {
+                    mismatch(entry);
+                }
+                import_ref.object_name = old_fname;
+                println!("Renamed import: {} -> {}", new.name, old.name);
+            }
+            JournalEntry::ExportName { export, old, new } => {
+                let old_fname = asset.add_fname_with_number(&old.name, old.number);
+                let Some(export_ref) = asset.get_export_mut(PackageIndex::new(*export)) else {
+                    mismatch(entry);
+                };
+                let object_name = &mut export_ref.get_base_export_mut().object_name;
+                if JournalName::from(&*object_name) != *new {
+                    mismatch(entry);
+                }
+                *object_name = old_fname;
+                println!("Renamed export: {}: {} -> {}", export, new, old);
+            }
+            // the package name follows the file names of each run, so undoing renames it again
+            JournalEntry::PackageName { .. } => (),
+            JournalEntry::LevelActors {
+                level,
+                old_actors,
+                new_actors,
+                old_dependencies,
+                ..
+            } => {
+                let level_index = PackageIndex::new(*level);
+                if level_snapshot(asset, level_index).0 != *new_actors {
+                    mismatch(entry);
+                }
+                let Some(Export::LevelExport(level_export)) = asset.get_export_mut(level_index)
+                else {
+                    mismatch(entry);
+                };
+                level_export.actors = old_actors.iter().map(|i| PackageIndex::new(*i)).collect();
+                level_export
+                    .get_base_export_mut()
+                    .create_before_serialization_dependencies = old_dependencies
+                    .iter()
+                    .map(|i| PackageIndex::new(*i))
+                    .collect();
+                println!("Restored actor list of level {}", level);
+            }
+            JournalEntry::Property {
+                export,
+                path,
+                old,
+                new,
+            } => {
+                let old_fname = match old {
+                    JournalValue::Name(old) => {
+                        Some(asset.add_fname_with_number(&old.name, old.number))
+                    }
+                    JournalValue::Vector(_) => None,
+                };
+                let Some(normal_export) = asset
+                    .get_export_mut(PackageIndex::new(*export))
+                    .and_then(|export| export.get_normal_export_mut())
+                else {
+                    mismatch(entry);
+                };
+                let Some(prop) = find_property_mut(&mut normal_export.properties, path) else {
+                    mismatch(entry);
+                };
+                match (prop, old, new) {
+                    (
+                        Property::NameProperty(prop),
+                        JournalValue::Name(_),
+                        JournalValue::Name(new),
+                    ) if JournalName::from(&prop.value) == *new => {
+                        prop.value = old_fname.unwrap();
+                    }
+                    (
+                        Property::VectorProperty(VectorProperty { value, .. })
+                        | Property::RotatorProperty(RotatorProperty { value, .. }),
+                        JournalValue::Vector(old),
+                        JournalValue::Vector(new),
+                    ) if value.x.0 == new.x && value.y.0 == new.y && value.z.0 == new.z => {
+                        value.x.0 = old.x;
+                        value.y.0 = old.y;
+                        value.z.0 = old.z;
                     }
+                    _ => mismatch(entry),
                 }
+                println!("Restored export property: {}: {}", export, path.join("."));
             }
-            // TODO figure out if import already exists and re-use
-            let mut imports_to_transplant = vec![];
-            let mut import_map = HashMap::new();
-            {
-                for export in &exports_to_transplant {
-                    for dep in export
-                        .get_base_export()
-                        .create_before_serialization_dependencies
-                        .iter()
-                        .chain(
-                            export
-                                .get_base_export()
-                                .serialization_before_create_dependencies
-                                .iter(),
-                        )
-                    {
-                        if dep.index >= 0 {
-                            continue;
-                        }
-                        if import_map.contains_key(&dep.index) {
-                            continue;
-                        }
-                        let import = donor_asset.get_import(*dep).unwrap();
-                        imports_to_transplant.push(import.clone());
-                        import_map.insert(dep.index, imports_to_transplant.len() as i32);
-                        if import_map.contains_key(&import.outer_index.index) {
-                            continue;
-                        }
-                        let parent_import = donor_asset.get_import(import.outer_index).unwrap();
-                        imports_to_transplant.push(parent_import.clone());
-                        import_map
-                            .insert(import.outer_index.index, imports_to_transplant.len() as i32);
+            JournalEntry::Transform {
+                component,
+                property,
+                old,
+                new,
+            } => {
+                let Some(normal_export) = asset
+                    .get_export_mut(PackageIndex::new(*component))
+                    .and_then(|export| export.get_normal_export_mut())
+                else {
+                    mismatch(entry);
+                };
+                let props = &mut normal_export.properties;
+                if get_struct_vector(props, property).as_ref() != Some(new) {
+                    mismatch(entry);
+                }
+                match old {
+                    Some(old) => {
+                        set_struct_vector(props, property, old);
                     }
+                    None => props.retain(|prop| {
+                        !matches!(prop, Property::StructProperty(prop) if prop.name.get_owned_content() == *property)
+                    }),
                 }
+                println!("Restored export property: {}: {}", component, property);
             }
+            JournalEntry::Structural { .. } => unreachable!(),
+        }
+    }
+}
 
-            let mut export_tuples: Vec<(i32, i32)> = export_map
-                .iter()
-                .map(|(&k, &v)| (k, v + asset.asset_data.exports.len() as i32))
-                .collect();
-            let mut import_tuples: Vec<(i32, i32)> = import_map
-                .iter()
-                .map(|(&k, &v)| (k, -(asset.imports.len() as i32 + v)))
-                .collect();
+/// Finds a property by name, or by structname.propname inside a struct property
+fn find_property_mut<'a>(props: &'a mut [Property], path: &[String]) -> Option<&'a mut Property> {
+    let (name, rest) = path.split_first()?;
+    let prop = props
+        .iter_mut()
+        .find(|prop| prop.get_name().get_owned_content() == *name)?;
+    if rest.is_empty() {
+        return Some(prop);
+    }
+    let Property::StructProperty(struct_prop) = prop else {
+        return None;
+    };
+    find_property_mut(&mut struct_prop.value, rest)
+}
+
+/// Makes the level's preload dependencies match the exports it holds: every actor, model
+/// component and other referenced export is created before the level is serialized
+fn rebuild_level_dependencies(asset: &mut Asset<File>, level_index: PackageIndex) {
+    let num_exports = asset.asset_data.exports.len() as i32;
+    let Some(Export::LevelExport(level)) = asset.get_export_mut(level_index) else {
+        eprintln!("export {} is not a level", level_index.index);
+        panic!();
+    };
+    let mut held = level.actors.clone();
+    held.extend(level.model_components.iter().copied());
+    held.extend([
+        level.model,
+        level.level_script,
+        level.nav_list_start,
+        level.nav_list_end,
+    ]);
+    let deps = &mut level
+        .get_base_export_mut()
+        .create_before_serialization_dependencies;
+    deps.retain(|dep| dep.index != 0 && dep.index <= num_exports);
+    for index in held {
+        if index.index > 0 && !deps.iter().any(|dep| dep.index == index.index) {
+            deps.push(index);
+        }
+    }
+}
 
-            export_tuples.sort_by_key(|&(_, dst)| dst);
-            import_tuples.sort_by_key(|&(_, dst)| dst);
+fn remove_null_dependencies(asset: &mut Asset<File>) {
+    for export in &mut asset.asset_data.exports {
+        remove_null_export_dependencies(export);
+    }
+}
 
-            for &(src, dst) in &export_tuples {
-                let name = donor_asset
-                    .get_export(PackageIndex::new(src))
-                    .unwrap()
-                    .get_base_export()
-                    .object_name
-                    .get_owned_content();
-                println!("Transplanting export: {} <- {} \"{}\"", dst, src, name);
+fn remove_null_export_dependencies(export: &mut Export) {
+    let base_export = export.get_base_export_mut();
+    for deps in [
+        &mut base_export.serialization_before_serialization_dependencies,
+        &mut base_export.create_before_serialization_dependencies,
+        &mut base_export.serialization_before_create_dependencies,
+        &mut base_export.create_before_create_dependencies,
+    ] {
+        deps.retain(|dep| dep.index != 0);
+    }
+}
+
+/// Visits every PackageIndex in the asset, passing the index of the import or export that holds
+/// it and a description of where it is stored
+fn for_each_package_index<F>(asset: &mut Asset<File>, f: &mut F)
+where
+    F: FnMut(i32, &str, &mut PackageIndex),
+{
+    for (i, import) in asset.imports.iter_mut().enumerate() {
+        f(-(i as i32 + 1), "outer_index", &mut import.outer_index);
+    }
+    for (i, export) in asset.asset_data.exports.iter_mut().enumerate() {
+        for_each_export_package_index(i as i32 + 1, export, f);
+    }
+}
+
+/// Visits every PackageIndex held by one export, passing owner through to f
+fn for_each_export_package_index<F>(owner: i32, export: &mut Export, f: &mut F)
+where
+    F: FnMut(i32, &str, &mut PackageIndex),
+{
+    let base_export = export.get_base_export_mut();
+    f(owner, "class_index", &mut base_export.class_index);
+    f(owner, "super_index", &mut base_export.super_index);
+    f(owner, "template_index", &mut base_export.template_index);
+    f(owner, "outer_index", &mut base_export.outer_index);
+    for dep in &mut base_export.serialization_before_serialization_dependencies {
+        f(
+            owner,
+            "serialization_before_serialization_dependencies",
+            dep,
+        );
+    }
+    for dep in &mut base_export.create_before_serialization_dependencies {
+        f(owner, "create_before_serialization_dependencies", dep);
+    }
+    for dep in &mut base_export.serialization_before_create_dependencies {
+        f(owner, "serialization_before_create_dependencies", dep);
+    }
+    for dep in &mut base_export.create_before_create_dependencies {
+        f(owner, "create_before_create_dependencies", dep);
+    }
+    for_each_export_prop(export, &mut |prop| {
+        let field = prop.get_name().get_owned_content();
+        match prop {
+            Property::ObjectProperty(p) => f(owner, &field, &mut p.value),
+            Property::DelegateProperty(p) => f(owner, &field, &mut p.value.object),
+            Property::MulticastDelegateProperty(p) => {
+                for delegate in &mut p.value {
+                    f(owner, &field, &mut delegate.object);
+                }
+            }
+            Property::MulticastInlineDelegateProperty(p) => {
+                for delegate in &mut p.value {
+                    f(owner, &field, &mut delegate.object);
+                }
+            }
+            Property::MulticastSparseDelegateProperty(p) => {
+                for delegate in &mut p.value {
+                    f(owner, &field, &mut delegate.object);
+                }
+            }
+            Property::FieldPathProperty(p) => f(owner, &field, &mut p.value.resolved_owner),
+            _ => (),
+        }
+    });
+    if let Export::LevelExport(level) = export {
+        for actor in &mut level.actors {
+            f(owner, "actors", actor);
+        }
+        f(owner, "model", &mut level.model);
+        for model_component in &mut level.model_components {
+            f(owner, "model_components", model_component);
+        }
+        f(owner, "level_script", &mut level.level_script);
+        f(owner, "nav_list_start", &mut level.nav_list_start);
+        f(owner, "nav_list_end", &mut level.nav_list_end);
+    }
+    if let Export::ClassExport(class_export) = export {
+        // function names can't be mutated in place so the map is rebuilt
+        class_export.func_map = std::mem::take(&mut class_export.func_map)
+            .into_iter()
+            .map(|(name, mut function)| {
+                f(owner, "func_map", &mut function);
+                (name, function)
+            })
+            .collect();
+        f(owner, "class_within", &mut class_export.class_within);
+        for interface in &mut class_export.interfaces {
+            let mut class = PackageIndex::new(interface.class);
+            f(owner, "interfaces", &mut class);
+            interface.class = class.index;
+        }
+        f(
+            owner,
+            "class_generated_by",
+            &mut class_export.class_generated_by,
+        );
+        f(
+            owner,
+            "class_default_object",
+            &mut class_export.class_default_object,
+        );
+    }
+    if let Some(struct_export) = get_struct_export_mut(export) {
+        if let Some(next) = &mut struct_export.field.next {
+            f(owner, "next", next);
+        }
+        f(owner, "super_struct", &mut struct_export.super_struct);
+        for child in &mut struct_export.children {
+            f(owner, "children", child);
+        }
+        for loaded_property in &mut struct_export.loaded_properties {
+            for package_index in fproperty_package_indices_mut(loaded_property) {
+                f(owner, "loaded_properties", package_index);
             }
-            for &(src, dst) in import_tuples.iter().rev() {
-                let name = donor_asset
-                    .get_import(PackageIndex::new(src))
+        }
+    }
+}
+
+/// Returns the struct part of a class, function or struct export
+fn get_struct_export(export: &Export) -> Option<&StructExport> {
+    match export {
+        Export::StructExport(struct_export) => Some(struct_export),
+        Export::ClassExport(class_export) => Some(&class_export.struct_export),
+        Export::FunctionExport(function_export) => Some(&function_export.struct_export),
+        Export::UserDefinedStructExport(user_defined_struct_export) => {
+            Some(&user_defined_struct_export.struct_export)
+        }
+        _ => None,
+    }
+}
+
+fn get_struct_export_mut(export: &mut Export) -> Option<&mut StructExport> {
+    match export {
+        Export::StructExport(struct_export) => Some(struct_export),
+        Export::ClassExport(class_export) => Some(&mut class_export.struct_export),
+        Export::FunctionExport(function_export) => Some(&mut function_export.struct_export),
+        Export::UserDefinedStructExport(user_defined_struct_export) => {
+            Some(&mut user_defined_struct_export.struct_export)
+        }
+        _ => None,
+    }
+}
+
+/// Returns the PackageIndex fields of a Blueprint property definition and the definitions inside
+/// it, such as an array's inner property. Every type is listed so that a new one that holds an
+/// index doesn't go unnoticed.
+fn fproperty_package_indices_mut(prop: &mut FProperty) -> Vec<&mut PackageIndex> {
+    match prop {
+        FProperty::FEnumProperty(p) => {
+            let mut indices = vec![&mut p.enum_value];
+            indices.extend(fproperty_package_indices_mut(&mut p.underlying_prop));
+            indices
+        }
+        FProperty::FArrayProperty(p) => fproperty_package_indices_mut(&mut p.inner),
+        FProperty::FSetProperty(p) => fproperty_package_indices_mut(&mut p.element_prop),
+        FProperty::FMapProperty(p) => {
+            let mut indices = fproperty_package_indices_mut(&mut p.key_prop);
+            indices.extend(fproperty_package_indices_mut(&mut p.value_prop));
+            indices
+        }
+        FProperty::FObjectProperty(p) => vec![&mut p.property_class],
+        FProperty::FSoftObjectProperty(p) => vec![&mut p.property_class],
+        FProperty::FClassProperty(p) => vec![&mut p.property_class, &mut p.meta_class],
+        FProperty::FSoftClassProperty(p) => vec![&mut p.property_class, &mut p.meta_class],
+        FProperty::FDelegateProperty(p) => vec![&mut p.signature_function],
+        FProperty::FMulticastDelegateProperty(p) => vec![&mut p.signature_function],
+        FProperty::FMulticastInlineDelegateProperty(p) => vec![&mut p.signature_function],
+        FProperty::FMulticastSparseDelegateProperty(p) => vec![&mut p.signature_function],
+        FProperty::FInterfaceProperty(p) => vec![&mut p.interface_class],
+        FProperty::FByteProperty(p) => vec![&mut p.enum_value],
+        FProperty::FStructProperty(p) => vec![&mut p.struct_value],
+        FProperty::FBoolProperty(_)
+        | FProperty::FNumericProperty(_)
+        | FProperty::FGenericProperty(_) => {
+            vec![]
+        }
+    }
+}
+
+/// Returns the exports whose PackageIndex fields can't all be visited: Blueprint bytecode and
+/// old style property definitions aren't parsed far enough to renumber the indices inside them
+fn find_unvisited_index_exports(asset: &Asset<File>) -> Vec<(i32, &'static str)> {
+    let mut unvisited = vec![];
+    for (i, export) in asset.asset_data.exports.iter().enumerate() {
+        let has_bytecode = get_struct_export(export).is_some_and(|struct_export| {
+            struct_export
+                .script_bytecode
+                .as_ref()
+                .is_some_and(|bytecode| !bytecode.is_empty())
+                || struct_export
+                    .script_bytecode_raw
+                    .as_ref()
+                    .is_some_and(|bytecode| !bytecode.is_empty())
+        });
+        if has_bytecode {
+            unvisited.push((i as i32 + 1, "Blueprint bytecode"));
+        } else if let Export::PropertyExport(_) = export {
+            unvisited.push((i as i32 + 1, "property definition"));
+        }
+    }
+    unvisited
+}
+
+/// Refuses to renumber imports or exports in an asset with indices that can't be renumbered,
+/// listing the exports that hold them
+fn check_indices_can_be_renumbered(asset: &Asset<File>, action: &str) {
+    let unvisited = find_unvisited_index_exports(asset);
+    if unvisited.is_empty() {
+        return;
+    }
+    for (index, kind) in &unvisited {
+        eprintln!(
+            "  export {} \"{}\": {}",
+            index,
+            fname_to_string(
+                &asset
+                    .get_export(PackageIndex::new(*index))
                     .unwrap()
+                    .get_base_export()
                     .object_name
-                    .get_owned_content();
-                println!("Transplanting import: {} <- {} \"{}\"", dst, src, name);
-            }
+            ),
+            kind
+        );
+    }
+    eprintln!(
+        "cannot {}: the indices inside these exports can't be renumbered",
+        action
+    );
+    panic!();
+}
 
-            let export_map: HashMap<i32, i32> = export_tuples.into_iter().collect();
-            let import_map: HashMap<i32, i32> = import_tuples.into_iter().collect();
+/// Read-only version of for_each_package_index, visiting the same fields
+fn for_each_package_index_ref<F>(asset: &Asset<File>, f: &mut F)
+where
+    F: FnMut(i32, &str, PackageIndex),
+{
+    for (i, import) in asset.imports.iter().enumerate() {
+        f(-(i as i32 + 1), "outer_index", import.outer_index);
+    }
+    for (i, export) in asset.asset_data.exports.iter().enumerate() {
+        for_each_export_package_index_ref(i as i32 + 1, export, f);
+    }
+}
 
-            let expected_combined_size = export_map.len() + import_map.len() + 1;
-            let mut combined_map = HashMap::new();
-            combined_map.extend(export_map);
-            combined_map.extend(import_map);
-            combined_map.insert(
-                donor_persistent_level_index.index,
-                persistent_level_index.index,
-            );
-            assert_eq!(expected_combined_size, combined_map.len());
-
-            for export in &mut exports_to_transplant {
-                let base_export = export.get_base_export_mut();
-                base_export.object_name =
-                    asset.add_fname(&base_export.object_name.get_owned_content());
-                base_export.class_index.index = *combined_map
-                    .get(&base_export.class_index.index)
-                    .unwrap_or(&base_export.class_index.index);
-                base_export.super_index.index = *combined_map
-                    .get(&base_export.super_index.index)
-                    .unwrap_or(&base_export.super_index.index);
-                base_export.template_index.index = *combined_map
-                    .get(&base_export.template_index.index)
-                    .unwrap_or(&base_export.template_index.index);
-                base_export.outer_index.index = *combined_map
-                    .get(&base_export.outer_index.index)
-                    .unwrap_or(&base_export.outer_index.index);
-                for dep in &mut base_export.create_before_serialization_dependencies {
-                    dep.index = *combined_map.get(&dep.index).unwrap_or(&dep.index);
-                }
-                for dep in &mut base_export.serialization_before_create_dependencies {
-                    dep.index = *combined_map.get(&dep.index).unwrap_or(&dep.index);
-                }
-                for dep in &mut base_export.create_before_create_dependencies {
-                    dep.index = *combined_map.get(&dep.index).unwrap_or(&dep.index);
-                }
-                for_each_prop(
-                    &mut export.get_normal_export_mut().unwrap().properties,
-                    &mut |prop| {
-                        match prop {
-                            Property::NameProperty(p) => {
-                                p.name = asset.add_fname(&p.name.get_owned_content())
-                            }
-                            Property::ObjectProperty(p) => {
-                                p.name = asset.add_fname(&p.name.get_owned_content())
-                            }
-                            Property::ArrayProperty(p) => {
-                                p.name = asset.add_fname(&p.name.get_owned_content())
-                            }
-                            Property::StructProperty(p) => {
-                                p.name = asset.add_fname(&p.name.get_owned_content());
-                                // setting struct type is necessary or else unreal_asset fails to parse
-                                // it in the dst asset
-                                let st = p.struct_type.clone();
-                                if p.struct_type.is_some() {
-                                    p.struct_type
-                                        .replace(asset.add_fname(&st.unwrap().get_owned_content()));
-                                }
-                            }
-                            Property::VectorProperty(p) => {
-                                p.name = asset.add_fname(&p.name.get_owned_content())
-                            }
-                            Property::RotatorProperty(p) => {
-                                p.name = asset.add_fname(&p.name.get_owned_content())
-                            }
-                            Property::ByteProperty(p) => {
-                                p.name = asset.add_fname(&p.name.get_owned_content())
-                            }
-                            Property::FloatProperty(p) => {
-                                p.name = asset.add_fname(&p.name.get_owned_content())
-                            }
-                            Property::IntProperty(p) => {
-                                p.name = asset.add_fname(&p.name.get_owned_content())
-                            }
-                            Property::BoolProperty(p) => {
-                                p.name = asset.add_fname(&p.name.get_owned_content())
-                            }
-                            Property::EnumProperty(p) => {
-                                p.name = asset.add_fname(&p.name.get_owned_content());
-                                let ev = p.value.clone();
-                                if p.value.is_some() {
-                                    p.value
-                                        .replace(asset.add_fname(&ev.unwrap().get_owned_content()));
-                                }
-                                // unclear if necessary
-                                let et = p.enum_type.clone();
-                                if p.enum_type.is_some() {
-                                    p.enum_type
-                                        .replace(asset.add_fname(&et.unwrap().get_owned_content()));
-                                }
-                            }
-                            Property::MulticastSparseDelegateProperty(p) => {
-                                p.name = asset.add_fname(&p.name.get_owned_content())
-                            }
-                            _ => {
-                                print!("unhandled property type: ");
-                                dbg!(&prop);
-                                panic!();
-                            }
-                        }
-                    },
-                );
-                for_each_obj_prop(
-                    &mut export.get_normal_export_mut().unwrap().properties,
-                    &mut |obj_prop| {
-                        if obj_prop.value.index != 0 {
-                            obj_prop.value.index =
-                                *combined_map.get(&obj_prop.value.index).unwrap();
-                        }
-                    },
-                );
-                for_each_name_prop(
-                    &mut export.get_normal_export_mut().unwrap().properties,
-                    &mut |name_prop| {
-                        name_prop.value = asset.add_fname(&name_prop.value.get_owned_content());
-                        name_prop.name = asset.add_fname(&name_prop.name.get_owned_content());
-                    },
-                );
+/// Read-only version of for_each_export_package_index, visiting the same fields
+fn for_each_export_package_index_ref<F>(owner: i32, export: &Export, f: &mut F)
+where
+    F: FnMut(i32, &str, PackageIndex),
+{
+    let base_export = export.get_base_export();
+    f(owner, "class_index", base_export.class_index);
+    f(owner, "super_index", base_export.super_index);
+    f(owner, "template_index", base_export.template_index);
+    f(owner, "outer_index", base_export.outer_index);
+    for dep in &base_export.serialization_before_serialization_dependencies {
+        f(
+            owner,
+            "serialization_before_serialization_dependencies",
+            *dep,
+        );
+    }
+    for dep in &base_export.create_before_serialization_dependencies {
+        f(owner, "create_before_serialization_dependencies", *dep);
+    }
+    for dep in &base_export.serialization_before_create_dependencies {
+        f(owner, "serialization_before_create_dependencies", *dep);
+    }
+    for dep in &base_export.create_before_create_dependencies {
+        f(owner, "create_before_create_dependencies", *dep);
+    }
+    for_each_export_prop_ref(export, &mut |prop| {
+        let field = prop.get_name().get_owned_content();
+        match prop {
+            Property::ObjectProperty(p) => f(owner, &field, p.value),
+            Property::DelegateProperty(p) => f(owner, &field, p.value.object),
+            Property::MulticastDelegateProperty(p) => {
+                for delegate in &p.value {
+                    f(owner, &field, delegate.object);
+                }
             }
-
-            for import in &mut imports_to_transplant {
-                import.class_package = asset.add_fname(&import.class_package.get_owned_content());
-                import.class_name = asset.add_fname(&import.class_name.get_owned_content());
-                import.object_name = asset.add_fname(&import.object_name.get_owned_content());
-                if import.outer_index.index != 0 {
-                    import.outer_index.index =
-                        *combined_map.get(&import.outer_index.index).unwrap();
+            Property::MulticastInlineDelegateProperty(p) => {
+                for delegate in &p.value {
+                    f(owner, &field, delegate.object);
                 }
             }
-
-            if let Export::LevelExport(persistent_level) =
-                asset.get_export_mut(persistent_level_index).unwrap()
-            {
-                let actor_index = PackageIndex::new(*combined_map.get(&root_index).unwrap());
-                persistent_level.actors.push(actor_index);
-                persistent_level
-                    .get_base_export_mut()
-                    .create_before_serialization_dependencies
-                    .push(actor_index);
-            } else {
-                panic!();
+            Property::MulticastSparseDelegateProperty(p) => {
+                for delegate in &p.value {
+                    f(owner, &field, delegate.object);
+                }
+            }
+            Property::FieldPathProperty(p) => f(owner, &field, p.value.resolved_owner),
+            _ => (),
+        }
+    });
+    if let Export::LevelExport(level) = export {
+        for actor in &level.actors {
+            f(owner, "actors", *actor);
+        }
+        f(owner, "model", level.model);
+        for model_component in &level.model_components {
+            f(owner, "model_components", *model_component);
+        }
+        f(owner, "level_script", level.level_script);
+        f(owner, "nav_list_start", level.nav_list_start);
+        f(owner, "nav_list_end", level.nav_list_end);
+    }
+    if let Export::ClassExport(class_export) = export {
+        for function in class_export.func_map.values() {
+            f(owner, "func_map", *function);
+        }
+        f(owner, "class_within", class_export.class_within);
+        for interface in &class_export.interfaces {
+            f(owner, "interfaces", PackageIndex::new(interface.class));
+        }
+        f(owner, "class_generated_by", class_export.class_generated_by);
+        f(
+            owner,
+            "class_default_object",
+            class_export.class_default_object,
+        );
+    }
+    if let Some(struct_export) = get_struct_export(export) {
+        if let Some(next) = struct_export.field.next {
+            f(owner, "next", next);
+        }
+        f(owner, "super_struct", struct_export.super_struct);
+        for child in &struct_export.children {
+            f(owner, "children", *child);
+        }
+        for loaded_property in &struct_export.loaded_properties {
+            // cloned so the same list of fields can be used
+            let mut loaded_property = loaded_property.clone();
+            for package_index in fproperty_package_indices_mut(&mut loaded_property) {
+                f(owner, "loaded_properties", *package_index);
             }
+        }
+    }
+}
 
-            asset
-                .asset_data
-                .exports
-                .extend_from_slice(&exports_to_transplant);
-            asset.imports.extend_from_slice(&imports_to_transplant);
+fn find_or_add_import(
+    asset: &mut Asset<File>,
+    class_package: &str,
+    class_name: &str,
+    outer_index: PackageIndex,
+    object_name: &str,
+) -> PackageIndex {
+    if let Some(index) = find_import(asset, class_package, class_name, outer_index, object_name) {
+        println!("Found existing import: {} \"{}\"", index.index, object_name);
+        return index;
+    }
+    let index = add_import(asset, class_package, class_name, outer_index, object_name);
+    println!("Added import: {} \"{}\"", index.index, object_name);
+    index
+}
+
+fn add_import(
+    asset: &mut Asset<File>,
+    class_package: &str,
+    class_name: &str,
+    outer_index: PackageIndex,
+    object_name: &str,
+) -> PackageIndex {
+    let import = Import::new(
+        asset.add_fname(class_package),
+        asset.add_fname(class_name),
+        outer_index,
+        asset.add_fname(object_name),
+        false,
+    );
+    asset.imports.push(import);
+    PackageIndex::new(-(asset.imports.len() as i32))
+}
+
+/// Returns the index of an import in asset with the same path as an import in donor_asset,
+/// transplanting it (and any outers that are missing) if asset doesn't import it yet. Resolved
+/// indices are recorded in import_map.
+fn transplant_import(
+    asset: &mut Asset<File>,
+    donor_asset: &Asset<File>,
+    donor_index: PackageIndex,
+    import_map: &mut HashMap<i32, i32>,
+) -> PackageIndex {
+    if let Some(&index) = import_map.get(&donor_index.index) {
+        return PackageIndex::new(index);
+    }
+    let import = donor_asset.get_import(donor_index).unwrap();
+    let outer_index = if import.outer_index.index == 0 {
+        import.outer_index
+    } else {
+        transplant_import(asset, donor_asset, import.outer_index, import_map)
+    };
+    let object_name = fname_to_string(&import.object_name);
+    let index = match find_import(
+        asset,
+        &fname_to_string(&import.class_package),
+        &fname_to_string(&import.class_name),
+        outer_index,
+        &object_name,
+    ) {
+        Some(index) => {
+            println!(
+                "Reusing import: {} <- {} \"{}\"",
+                index.index, donor_index.index, object_name
+            );
+            index
+        }
+        None => {
+            // the names keep their numbers and the import stays optional if it was
+            let new_import = Import::new(
+                rehome_fname(asset, &import.class_package),
+                rehome_fname(asset, &import.class_name),
+                outer_index,
+                rehome_fname(asset, &import.object_name),
+                import.optional,
+            );
+            asset.imports.push(new_import);
+            let index = PackageIndex::new(-(asset.imports.len() as i32));
+            println!(
+                "Transplanting import: {} <- {} \"{}\"",
+                index.index, donor_index.index, object_name
+            );
+            index
         }
+    };
+    import_map.insert(donor_index.index, index.index);
+    index
+}
+
+/// Re-registers an FName, keeping its number, in asset's name map
+fn rehome_fname(asset: &mut Asset<File>, fname: &FName) -> FName {
+    asset.add_fname_with_number(&fname.get_owned_content(), fname.get_number())
+}
+
+/// Re-registers every FName stored directly in a property in asset's name map so the property
+/// can be written into asset. Nested properties are reached through for_each_prop.
+fn rehome_property_fnames(asset: &mut Asset<File>, prop: &mut Property) {
+    let name = rehome_fname(asset, prop.get_name_mut());
+    *prop.get_name_mut() = name;
+    for fname in property_fnames_mut(prop) {
+        *fname = rehome_fname(asset, fname);
     }
+}
 
-    asset
-        .write_data(&mut output_uasset_file, Some(&mut output_uexp_file))
-        .unwrap();
+/// Returns the FNames stored directly in a property besides its name, not counting nested
+/// properties. Every property type is listed so that a new one has to be looked at here.
+fn property_fnames_mut(prop: &mut Property) -> Vec<&mut FName> {
+    match prop {
+        // setting struct type is necessary or else unreal_asset fails to parse it in the dst asset
+        Property::StructProperty(p) => p.struct_type.iter_mut().collect(),
+        Property::ArrayProperty(p) => p.array_type.iter_mut().collect(),
+        Property::SetProperty(p) => p
+            .array_type
+            .iter_mut()
+            .chain(&mut p.value.array_type)
+            .chain(&mut p.removed_items.array_type)
+            .collect(),
+        Property::MapProperty(p) => vec![&mut p.key_type, &mut p.value_type],
+        Property::EnumProperty(p) => p
+            .value
+            .iter_mut()
+            .chain(&mut p.enum_type)
+            .chain(&mut p.inner_type)
+            .collect(),
+        Property::ByteProperty(p) => {
+            let mut fnames: Vec<_> = p.enum_type.iter_mut().collect();
+            if let BytePropertyValue::FName(value) = &mut p.value {
+                fnames.push(value);
+            }
+            fnames
+        }
+        Property::NameProperty(p) => vec![&mut p.value],
+        Property::TextProperty(p) => p.table_id.iter_mut().collect(),
+        Property::SoftObjectProperty(p) => {
+            let asset_path = &mut p.value.asset_path;
+            asset_path
+                .package_name
+                .iter_mut()
+                .chain([&mut asset_path.asset_name])
+                .collect()
+        }
+        Property::SoftAssetPathProperty(p) => soft_path_fnames_mut(&mut p.value),
+        Property::SoftObjectPathProperty(p) => soft_path_fnames_mut(&mut p.value),
+        Property::SoftClassPathProperty(p) => soft_path_fnames_mut(&mut p.value),
+        Property::GameplayTagContainerProperty(p) => p.value.iter_mut().collect(),
+        Property::SmartNameProperty(p) => vec![&mut p.display_name],
+        Property::FieldPathProperty(p) => p.value.path.iter_mut().collect(),
+        Property::UniqueNetIdProperty(p) => p.value.iter_mut().map(|id| &mut id.ty).collect(),
+        Property::DelegateProperty(p) => vec![&mut p.value.delegate],
+        Property::MulticastDelegateProperty(p) => {
+            p.value.iter_mut().map(|d| &mut d.delegate).collect()
+        }
+        Property::MulticastInlineDelegateProperty(p) => {
+            p.value.iter_mut().map(|d| &mut d.delegate).collect()
+        }
+        Property::MulticastSparseDelegateProperty(p) => {
+            p.value.iter_mut().map(|d| &mut d.delegate).collect()
+        }
+        Property::NiagaraVariableProperty(p) => p
+            .struct_property
+            .struct_type
+            .iter_mut()
+            .chain([&mut p.variable_name])
+            .collect(),
+        Property::NiagaraVariableWithOffsetProperty(p) => {
+            let variable = &mut p.niagara_variable;
+            variable
+                .struct_property
+                .struct_type
+                .iter_mut()
+                .chain([&mut variable.variable_name])
+                .collect()
+        }
+        Property::MaterialAttributesInputProperty(p) => vec![
+            &mut p.value.name,
+            &mut p.value.input_name,
+            &mut p.value.expression_name,
+        ],
+        Property::ExpressionInputProperty(p) => vec![
+            &mut p.value.name,
+            &mut p.value.input_name,
+            &mut p.value.expression_name,
+        ],
+        Property::ColorMaterialInputProperty(p) => vec![
+            &mut p.material_expression.name,
+            &mut p.material_expression.input_name,
+            &mut p.material_expression.expression_name,
+            &mut p.value.name,
+        ],
+        Property::ScalarMaterialInputProperty(p) => vec![
+            &mut p.material_expression.name,
+            &mut p.material_expression.input_name,
+            &mut p.material_expression.expression_name,
+        ],
+        Property::ShadingModelMaterialInputProperty(p) => vec![
+            &mut p.material_expression.name,
+            &mut p.material_expression.input_name,
+            &mut p.material_expression.expression_name,
+        ],
+        Property::VectorMaterialInputProperty(p) => vec![
+            &mut p.material_expression.name,
+            &mut p.material_expression.input_name,
+            &mut p.material_expression.expression_name,
+            &mut p.value.name,
+        ],
+        Property::Vector2MaterialInputProperty(p) => vec![
+            &mut p.material_expression.name,
+            &mut p.material_expression.input_name,
+            &mut p.material_expression.expression_name,
+            &mut p.value.name,
+        ],
+        Property::MovieSceneEventParametersProperty(p) => {
+            let asset_path = &mut p.value.struct_type.asset_path;
+            asset_path
+                .package_name
+                .iter_mut()
+                .chain([&mut asset_path.asset_name])
+                .collect()
+        }
+        Property::UnknownProperty(p) => p.serialized_type.iter_mut().collect(),
+        Property::BoolProperty(_)
+        | Property::UInt16Property(_)
+        | Property::UInt32Property(_)
+        | Property::UInt64Property(_)
+        | Property::FloatProperty(_)
+        | Property::Int16Property(_)
+        | Property::Int64Property(_)
+        | Property::Int8Property(_)
+        | Property::IntProperty(_)
+        | Property::DoubleProperty(_)
+        | Property::StrProperty(_)
+        | Property::ObjectProperty(_)
+        | Property::AssetObjectProperty(_)
+        | Property::IntPointProperty(_)
+        | Property::VectorProperty(_)
+        | Property::ColorProperty(_)
+        | Property::LinearColorProperty(_)
+        | Property::Vector4Property(_)
+        | Property::Vector2DProperty(_)
+        | Property::BoxProperty(_)
+        | Property::Box2DProperty(_)
+        | Property::QuatProperty(_)
+        | Property::RotatorProperty(_)
+        | Property::PlaneProperty(_)
+        | Property::TimeSpanProperty(_)
+        | Property::DateTimeProperty(_)
+        | Property::GuidProperty(_)
+        | Property::PerPlatformBoolProperty(_)
+        | Property::PerPlatformIntProperty(_)
+        | Property::PerPlatformFloatProperty(_)
+        | Property::WeightedRandomSamplerProperty(_)
+        | Property::SkeletalMeshSamplingLODBuiltDataProperty(_)
+        | Property::SkeletalMeshAreaWeightedTriangleSampler(_)
+        | Property::RichCurveKeyProperty(_)
+        | Property::ViewTargetBlendParamsProperty(_)
+        | Property::ClothLodDataProperty(_)
+        | Property::FontCharacterProperty(_)
+        | Property::FontDataProperty(_)
+        | Property::MovieSceneEvalTemplatePtrProperty(_)
+        | Property::MovieSceneTrackImplementationPtrProperty(_)
+        | Property::MovieSceneEvaluationFieldEntityTreeProperty(_)
+        | Property::MovieSceneSubSequenceTreeProperty(_)
+        | Property::MovieSceneSequenceInstanceDataPtrProperty(_)
+        | Property::SectionEvaluationDataTreeProperty(_)
+        | Property::MovieSceneTrackIdentifierProperty(_)
+        | Property::MovieSceneSegmentProperty(_)
+        | Property::MovieSceneTrackFieldDataProperty(_)
+        | Property::MovieSceneSegmentIdentifierProperty(_)
+        | Property::MovieSceneSequenceIdProperty(_)
+        | Property::MovieSceneEvaluationKeyProperty(_)
+        | Property::MovieSceneFloatValueProperty(_)
+        | Property::MovieSceneFloatChannelProperty(_)
+        | Property::MovieSceneFrameRangeProperty(_) => vec![],
+    }
 }
 
-fn find_persistent_level_index(asset: &Asset<File>) -> Option<PackageIndex> {
-    for (i, export) in asset.asset_data.exports.iter().enumerate() {
-        let Export::LevelExport(export) = export else {
-            continue;
-        };
-        if export.get_base_export().object_name.get_owned_content() != "PersistentLevel" {
-            continue;
+/// Returns the package and asset FNames of a soft path, which older engine versions store as a
+/// plain string instead
+fn soft_path_fnames_mut(value: &mut SoftObjectPathPropertyValue) -> Vec<&mut FName> {
+    match value {
+        SoftObjectPathPropertyValue::Old(_) => vec![],
+        SoftObjectPathPropertyValue::New(path) => {
+            let asset_path = &mut path.asset_path;
+            asset_path
+                .package_name
+                .iter_mut()
+                .chain([&mut asset_path.asset_name])
+                .collect()
         }
-        return Some(PackageIndex::new(i as i32 + 1));
     }
-    None
 }
 
 fn for_each_prop<F>(props: &mut [Property], f: &mut F)
@@ -658,35 +3541,157 @@ where
         match prop {
             Property::ArrayProperty(p) => for_each_prop(&mut p.value, f),
             Property::StructProperty(p) => for_each_prop(&mut p.value, f),
+            Property::NiagaraVariableProperty(p) => for_each_prop(&mut p.struct_property.value, f),
+            Property::NiagaraVariableWithOffsetProperty(p) => {
+                for_each_prop(&mut p.niagara_variable.struct_property.value, f)
+            }
+            Property::SetProperty(p) => {
+                for_each_prop(&mut p.value.value, f);
+                for_each_prop(&mut p.removed_items.value, f);
+            }
+            Property::MapProperty(p) => {
+                // map keys can't be mutated in place so the map is rebuilt
+                p.value = std::mem::take(&mut p.value)
+                    .into_iter()
+                    .map(|(mut key, mut value)| {
+                        for_each_prop(std::slice::from_mut(&mut key), f);
+                        for_each_prop(std::slice::from_mut(&mut value), f);
+                        (key, value)
+                    })
+                    .collect();
+            }
             _ => (),
         };
     }
 }
 
-fn for_each_obj_prop<F>(props: &mut [Property], f: &mut F)
+/// Read-only version of for_each_prop, which also doesn't have to rebuild maps
+fn for_each_prop_ref<F>(props: &[Property], f: &mut F)
 where
-    F: FnMut(&mut ObjectProperty),
+    F: FnMut(&Property),
 {
-    for prop in props.iter_mut() {
+    for prop in props {
+        f(prop);
         match prop {
-            Property::ObjectProperty(p) => f(p),
-            Property::ArrayProperty(p) => for_each_obj_prop(&mut p.value, f),
-            Property::StructProperty(p) => for_each_obj_prop(&mut p.value, f),
+            Property::ArrayProperty(p) => for_each_prop_ref(&p.value, f),
+            Property::StructProperty(p) => for_each_prop_ref(&p.value, f),
+            Property::NiagaraVariableProperty(p) => for_each_prop_ref(&p.struct_property.value, f),
+            Property::NiagaraVariableWithOffsetProperty(p) => {
+                for_each_prop_ref(&p.niagara_variable.struct_property.value, f)
+            }
+            Property::SetProperty(p) => {
+                for_each_prop_ref(&p.value.value, f);
+                for_each_prop_ref(&p.removed_items.value, f);
+            }
+            Property::MapProperty(p) => {
+                for (key, value) in &p.value {
+                    for_each_prop_ref(std::slice::from_ref(key), f);
+                    for_each_prop_ref(std::slice::from_ref(value), f);
+                }
+            }
             _ => (),
         };
     }
 }
 
-fn for_each_name_prop<F>(props: &mut [Property], f: &mut F)
+/// Visits every property of an export, including the rows of data tables
+fn for_each_export_prop<F>(export: &mut Export, f: &mut F)
 where
-    F: FnMut(&mut NameProperty),
+    F: FnMut(&mut Property),
 {
-    for prop in props.iter_mut() {
-        match prop {
-            Property::NameProperty(p) => f(p),
-            Property::ArrayProperty(p) => for_each_name_prop(&mut p.value, f),
-            Property::StructProperty(p) => for_each_name_prop(&mut p.value, f),
-            _ => (),
-        };
+    if let Some(normal_export) = export.get_normal_export_mut() {
+        for_each_prop(&mut normal_export.properties, f);
+    }
+    if let Export::DataTableExport(data_table) = export {
+        for row in &mut data_table.table.data {
+            // rows are bare struct properties so they are wrapped to be visited
+            let mut props = [Property::StructProperty(row.clone())];
+            for_each_prop(&mut props, f);
+            let [Property::StructProperty(new_row)] = props else {
+                unreachable!();
+            };
+            *row = new_row;
+        }
+    }
+}
+
+/// Read-only version of for_each_export_prop
+fn for_each_export_prop_ref<F>(export: &Export, f: &mut F)
+where
+    F: FnMut(&Property),
+{
+    if let Some(normal_export) = export.get_normal_export() {
+        for_each_prop_ref(&normal_export.properties, f);
+    }
+    if let Export::DataTableExport(data_table) = export {
+        for row in &data_table.table.data {
+            for_each_prop_ref(&[Property::StructProperty(row.clone())], f);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(x: f64, y: f64, z: f64) -> Vec3d {
+        Vec3d { x, y, z }
+    }
+
+    #[test]
+    fn box_region_contains_its_bounds_in_either_order() {
+        let region = Region::parse("box:100,100,500,-100,-100,0");
+        assert!(region.contains(&v(0.0, 0.0, 0.0)));
+        assert!(region.contains(&v(-100.0, 100.0, 500.0)));
+        assert!(!region.contains(&v(0.0, 0.0, -0.1)));
+        assert!(!region.contains(&v(101.0, 0.0, 10.0)));
+    }
+
+    #[test]
+    fn sphere_region_contains_its_surface() {
+        let region = Region::parse("sphere:10,0,0,100");
+        assert!(region.contains(&v(10.0, 0.0, 0.0)));
+        assert!(region.contains(&v(110.0, 0.0, 0.0)));
+        assert!(!region.contains(&v(10.0, 71.0, 71.0)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn region_with_wrong_number_of_values_is_rejected() {
+        Region::parse("sphere:0,0,0");
+    }
+
+    #[test]
+    fn fname_to_string_shows_number_minus_one() {
+        assert_eq!(fname_to_string(&FName::new_dummy("Cube".into(), 0)), "Cube");
+        assert_eq!(
+            fname_to_string(&FName::new_dummy("Cube".into(), 1)),
+            "Cube_0"
+        );
+        assert_eq!(
+            fname_to_string(&FName::new_dummy("Cube".into(), 4)),
+            "Cube_3"
+        );
+    }
+
+    #[test]
+    fn fname_matches_full_name_or_name_without_number() {
+        let fname = FName::new_dummy("Cube".into(), 3);
+        assert!(fname_matches(&fname, "Cube"));
+        assert!(fname_matches(&fname, "Cube_2"));
+        assert!(!fname_matches(&fname, "Cube_1"));
+    }
+
+    #[test]
+    fn free_name_number_keeps_unused_number() {
+        assert_eq!(free_name_number(0, &HashSet::new()), None);
+        assert_eq!(free_name_number(2, &HashSet::from([0, 1])), None);
+    }
+
+    #[test]
+    fn free_name_number_skips_used_numbers() {
+        assert_eq!(free_name_number(0, &HashSet::from([0])), Some(1));
+        assert_eq!(free_name_number(0, &HashSet::from([0, 1, 2])), Some(3));
+        assert_eq!(free_name_number(2, &HashSet::from([0, 2, 3, 5])), Some(4));
     }
 }
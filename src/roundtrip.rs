@@ -0,0 +1,171 @@
+use std::fs;
+use std::fs::File;
+use std::io::Cursor;
+use std::panic;
+use std::path::Path;
+use std::path::PathBuf;
+use unreal_asset::exports::ExportBaseTrait;
+use unreal_asset::Asset;
+
+use crate::fname_to_string;
+use crate::open_asset;
+
+/// Result of reading an asset and writing it back unchanged
+enum Outcome {
+    Identical,
+    Differs(String),
+    Failed(String),
+}
+
+/// Round-trips every .uasset and .umap under folder, printing one line per asset and a summary.
+/// Returns false if any asset failed to parse or didn't come back byte for byte.
+pub fn roundtrip_folder(folder: &Path) -> bool {
+    let mut uasset_paths = vec![];
+    let mut num_skipped = 0;
+    find_uassets(folder, &mut uasset_paths, &mut num_skipped);
+    uasset_paths.sort();
+
+    let (mut num_identical, mut num_differ, mut num_failed) = (0, 0, 0);
+    for uasset_path in &uasset_paths {
+        // unreal_asset panics on some malformed data, which shouldn't stop the rest of the folder
+        let outcome = panic::catch_unwind(|| roundtrip(uasset_path))
+            .unwrap_or_else(|_| Outcome::Failed("parser panicked".to_string()));
+        match outcome {
+            Outcome::Identical => {
+                num_identical += 1;
+                println!("OK: {}", uasset_path.display());
+            }
+            Outcome::Differs(description) => {
+                num_differ += 1;
+                println!("DIFF: {}: {}", uasset_path.display(), description);
+            }
+            Outcome::Failed(description) => {
+                num_failed += 1;
+                println!("FAIL: {}: {}", uasset_path.display(), description);
+            }
+        }
+    }
+    println!(
+        "{} assets: {} identical, {} differ, {} failed, {} other files skipped",
+        uasset_paths.len(),
+        num_identical,
+        num_differ,
+        num_failed,
+        num_skipped
+    );
+    num_differ == 0 && num_failed == 0
+}
+
+/// Collects the asset headers under folder, counting files that are neither a header nor data
+/// that belongs to one
+fn find_uassets(folder: &Path, uasset_paths: &mut Vec<PathBuf>, num_skipped: &mut usize) {
+    let entries = match fs::read_dir(folder) {
+        Ok(entries) => entries,
+        Err(err) => {
+            eprintln!("cannot read folder {}: {}", folder.display(), err);
+            panic!();
+        }
+    };
+    for entry in entries {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            find_uassets(&path, uasset_paths, num_skipped);
+            continue;
+        }
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("uasset" | "umap") => uasset_paths.push(path),
+            // read along with the header they belong to
+            Some("uexp" | "ubulk" | "uptnl") => (),
+            _ => *num_skipped += 1,
+        }
+    }
+}
+
+fn roundtrip(uasset_path: &Path) -> Outcome {
+    let asset = match open_asset(uasset_path) {
+        Ok(asset) => asset,
+        Err(err) => return Outcome::Failed(format!("cannot parse: {}", err)),
+    };
+    let uexp_path = uasset_path.with_extension("uexp");
+    let original_uasset = fs::read(uasset_path).unwrap();
+    let original_uexp = fs::read(&uexp_path).ok();
+
+    let mut uasset_cursor = Cursor::new(vec![]);
+    let mut uexp_cursor = Cursor::new(vec![]);
+    let written = match original_uexp {
+        Some(_) => asset.write_data(&mut uasset_cursor, Some(&mut uexp_cursor)),
+        None => asset.write_data(&mut uasset_cursor, None),
+    };
+    if let Err(err) = written {
+        return Outcome::Failed(format!("cannot write: {}", err));
+    }
+
+    if let Some(offset) = first_difference(&original_uasset, uasset_cursor.get_ref()) {
+        return Outcome::Differs(format!(
+            ".uasset differs at 0x{:x} ({})",
+            offset,
+            describe_offset(&asset, offset as i64)
+        ));
+    }
+    if let Some(original_uexp) = original_uexp {
+        if let Some(offset) = first_difference(&original_uexp, uexp_cursor.get_ref()) {
+            // export offsets count from the start of the .uasset as if the two were one file
+            return Outcome::Differs(format!(
+                ".uexp differs at 0x{:x} ({})",
+                offset,
+                describe_offset(&asset, (original_uasset.len() + offset) as i64)
+            ));
+        }
+    }
+    Outcome::Identical
+}
+
+/// Returns the first offset at which two files differ, counting a length mismatch as a difference
+/// at the end of the shorter one
+fn first_difference(original: &[u8], written: &[u8]) -> Option<usize> {
+    match original.iter().zip(written).position(|(a, b)| a != b) {
+        Some(offset) => Some(offset),
+        None if original.len() != written.len() => Some(original.len().min(written.len())),
+        None => None,
+    }
+}
+
+fn describe_offset(asset: &Asset<File>, offset: i64) -> String {
+    for (i, export) in asset.asset_data.exports.iter().enumerate() {
+        let base_export = export.get_base_export();
+        if (base_export.serial_offset..base_export.serial_offset + base_export.serial_size)
+            .contains(&offset)
+        {
+            return format!(
+                "export {} \"{}\" at 0x{:x}",
+                i + 1,
+                fname_to_string(&base_export.object_name),
+                offset - base_export.serial_offset
+            );
+        }
+    }
+    "outside every export, in the package header or trailing data".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identical_files_have_no_difference() {
+        assert_eq!(first_difference(b"", b""), None);
+        assert_eq!(first_difference(b"abc", b"abc"), None);
+    }
+
+    #[test]
+    fn first_differing_byte_is_reported() {
+        assert_eq!(first_difference(b"abc", b"xbc"), Some(0));
+        assert_eq!(first_difference(b"abcd", b"abxx"), Some(2));
+    }
+
+    #[test]
+    fn length_mismatch_is_reported_at_end_of_shorter_file() {
+        assert_eq!(first_difference(b"abc", b"abcde"), Some(3));
+        assert_eq!(first_difference(b"abcde", b"ab"), Some(2));
+    }
+}
@@ -0,0 +1,330 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fs::File;
+use unreal_asset::exports::Export;
+use unreal_asset::exports::ExportBaseTrait;
+use unreal_asset::properties::Property;
+use unreal_asset::properties::PropertyDataTrait;
+use unreal_asset::types::fname::FName;
+use unreal_asset::Asset;
+
+use crate::fname_to_string;
+use crate::for_each_export_prop_ref;
+use crate::for_each_package_index_ref;
+use crate::get_struct_export;
+use crate::property_fnames_mut;
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Finding {
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl Finding {
+    fn error(message: String) -> Finding {
+        Finding {
+            severity: Severity::Error,
+            message,
+        }
+    }
+
+    fn warning(message: String) -> Finding {
+        Finding {
+            severity: Severity::Warning,
+            message,
+        }
+    }
+}
+
+/// Checks an asset for the kinds of inconsistencies that edits can introduce
+pub fn validate(asset: &Asset<File>) -> Vec<Finding> {
+    let mut findings = vec![];
+    let num_imports = asset.imports.len() as i32;
+    let num_exports = asset.asset_data.exports.len() as i32;
+
+    let mut object_prop_targets = vec![];
+    for (i, export) in asset.asset_data.exports.iter().enumerate() {
+        for_each_export_prop_ref(export, &mut |prop| {
+            if let Property::ObjectProperty(obj_prop) = prop {
+                object_prop_targets.push((
+                    i as i32 + 1,
+                    obj_prop.name.get_owned_content(),
+                    obj_prop.value.index,
+                ));
+            }
+        });
+    }
+    // reported here rather than as plain out of range indices
+    let mut dangling_object_props = HashSet::new();
+    for (owner, name, target) in &object_prop_targets {
+        if *target > num_exports || *target < -num_imports {
+            dangling_object_props.insert((*owner, name.clone(), *target));
+            findings.push(Finding::error(format!(
+                "export {} {}: ObjectProperty target {} does not exist",
+                owner, name, target
+            )));
+        }
+    }
+
+    // every index has to point at an existing import or export
+    let mut referenced_imports = HashMap::new();
+    for_each_package_index_ref(asset, &mut |owner, field, package_index| {
+        let index = package_index.index;
+        let dangling_object_prop =
+            dangling_object_props.contains(&(owner, field.to_string(), index));
+        if (index > num_exports || index < -num_imports) && !dangling_object_prop {
+            findings.push(Finding::error(format!(
+                "{} {}: {} is out of range",
+                describe_owner(owner),
+                field,
+                index
+            )));
+        } else if index == 0 && field.ends_with("_dependencies") {
+            // a dependency on nothing can't be resolved when the asset is loaded
+            findings.push(Finding::error(format!(
+                "{} {}: null dependency",
+                describe_owner(owner),
+                field
+            )));
+        } else if index < 0 && owner != index {
+            referenced_imports
+                .entry(index)
+                .or_insert_with(|| format!("{} {}", describe_owner(owner), field));
+        }
+    });
+
+    // disabled imports are fine as long as nothing uses them
+    for (i, import) in asset.imports.iter().enumerate() {
+        let index = -(i as i32 + 1);
+        if import.outer_index.index != 0 || import.class_name.get_owned_content() == "Package" {
+            continue;
+        }
+        if let Some(user) = referenced_imports.get(&index) {
+            findings.push(Finding::warning(format!(
+                "import {} \"{}\" has outer 0 but is still referenced by {}",
+                index,
+                import.object_name.get_owned_content(),
+                user
+            )));
+        }
+    }
+
+    // assets cooked without dependency data have every list empty, so there is nothing to check
+    let has_dependencies = asset.asset_data.exports.iter().any(|export| {
+        let base_export = export.get_base_export();
+        !base_export
+            .serialization_before_serialization_dependencies
+            .is_empty()
+            || !base_export
+                .create_before_serialization_dependencies
+                .is_empty()
+            || !base_export
+                .serialization_before_create_dependencies
+                .is_empty()
+            || !base_export.create_before_create_dependencies.is_empty()
+    });
+    if has_dependencies {
+        for (owner, name, target) in &object_prop_targets {
+            if *target == 0 || *target > num_exports || *target < -num_imports {
+                continue;
+            }
+            let base_export = asset.asset_data.exports[*owner as usize - 1].get_base_export();
+            let listed = base_export
+                .serialization_before_serialization_dependencies
+                .iter()
+                .chain(&base_export.create_before_serialization_dependencies)
+                .chain(&base_export.serialization_before_create_dependencies)
+                .chain(&base_export.create_before_create_dependencies)
+                .any(|dep| dep.index == *target);
+            if !listed {
+                findings.push(Finding::warning(format!(
+                    "export {} {}: references {} without listing it as a dependency",
+                    owner, name, target
+                )));
+            }
+        }
+        for (i, export) in asset.asset_data.exports.iter().enumerate() {
+            let Export::LevelExport(level) = export else {
+                continue;
+            };
+            let deps = &level
+                .get_base_export()
+                .create_before_serialization_dependencies;
+            for actor in &level.actors {
+                if actor.index > 0 && !deps.iter().any(|dep| dep.index == actor.index) {
+                    findings.push(Finding::warning(format!(
+                        "level {}: actor {} is missing from its preload dependencies",
+                        i + 1,
+                        actor.index
+                    )));
+                }
+            }
+        }
+    }
+
+    // a Blueprint class holds its functions and is the class of its default object, so an index
+    // left pointing at the wrong export after exports were renumbered shows up here
+    for (i, export) in asset.asset_data.exports.iter().enumerate() {
+        let index = i as i32 + 1;
+        let is_inside = |target: i32| {
+            target > 0
+                && target <= num_exports
+                && asset.asset_data.exports[target as usize - 1]
+                    .get_base_export()
+                    .outer_index
+                    .index
+                    == index
+        };
+        if let Some(struct_export) = get_struct_export(export) {
+            for child in &struct_export.children {
+                if child.index > 0 && !is_inside(child.index) {
+                    findings.push(Finding::warning(format!(
+                        "export {} children: {} is not inside it",
+                        index, child.index
+                    )));
+                }
+            }
+        }
+        let Export::ClassExport(class_export) = export else {
+            continue;
+        };
+        for function in class_export.func_map.values() {
+            if function.index > 0 && !is_inside(function.index) {
+                findings.push(Finding::warning(format!(
+                    "export {} func_map: {} is not inside it",
+                    index, function.index
+                )));
+            }
+        }
+        let default_object = class_export.class_default_object.index;
+        if default_object > 0
+            && default_object <= num_exports
+            && asset.asset_data.exports[default_object as usize - 1]
+                .get_base_export()
+                .class_index
+                .index
+                != index
+        {
+            findings.push(Finding::warning(format!(
+                "export {} class_default_object: {} is not an instance of it",
+                index, default_object
+            )));
+        }
+    }
+
+    // the engine finds objects by path, so two objects with the same name under one outer collide
+    let mut export_paths = HashMap::new();
+    for (i, export) in asset.asset_data.exports.iter().enumerate() {
+        let base_export = export.get_base_export();
+        let path = (
+            base_export.outer_index.index,
+            fname_to_string(&base_export.object_name),
+        );
+        if let Some(first) = export_paths.insert(path.clone(), i as i32 + 1) {
+            findings.push(Finding::error(format!(
+                "exports {} and {} are both named \"{}\" under {}",
+                first,
+                i + 1,
+                path.1,
+                path.0
+            )));
+        }
+    }
+    let mut import_paths = HashMap::new();
+    for (i, import) in asset.imports.iter().enumerate() {
+        let path = (
+            import.outer_index.index,
+            import.class_name.get_owned_content(),
+            fname_to_string(&import.object_name),
+        );
+        if let Some(first) = import_paths.insert(path.clone(), -(i as i32 + 1)) {
+            findings.push(Finding::warning(format!(
+                "imports {} and {} are both {} \"{}\" under {}",
+                first,
+                -(i as i32 + 1),
+                path.1,
+                path.2,
+                path.0
+            )));
+        }
+    }
+
+    // names are written as name map indices, so a name that isn't in the map can't be saved
+    let name_map: HashSet<String> = asset
+        .get_name_map()
+        .borrow()
+        .get_name_map_index_list()
+        .iter()
+        .cloned()
+        .collect();
+    let mut missing_names = vec![];
+    for (i, import) in asset.imports.iter().enumerate() {
+        for fname in [
+            &import.class_package,
+            &import.class_name,
+            &import.object_name,
+        ] {
+            missing_names.push((format!("import {}", -(i as i32 + 1)), fname.clone()));
+        }
+    }
+    for (i, export) in asset.asset_data.exports.iter().enumerate() {
+        let owner = format!("export {}", i + 1);
+        missing_names.push((owner.clone(), export.get_base_export().object_name.clone()));
+        for_each_export_prop_ref(export, &mut |prop| {
+            for fname in property_fnames(prop) {
+                missing_names.push((owner.clone(), fname));
+            }
+        });
+    }
+    let mut reported = HashSet::new();
+    for (owner, fname) in missing_names {
+        let name = fname.get_owned_content();
+        if name_map.contains(&name) || !reported.insert(name.clone()) {
+            continue;
+        }
+        findings.push(Finding::error(format!(
+            "{}: name \"{}\" is not in the name map",
+            owner, name
+        )));
+    }
+
+    findings.sort_by_key(|finding| finding.severity);
+    findings
+}
+
+fn describe_owner(owner: i32) -> String {
+    match owner {
+        owner if owner < 0 => format!("import {}", owner),
+        owner => format!("export {}", owner),
+    }
+}
+
+/// Returns the FNames stored directly in a property, not counting nested properties
+fn property_fnames(prop: &Property) -> Vec<FName> {
+    // the list lives with the transplant code, which has to re-register the same names
+    let mut prop = prop.clone();
+    let mut fnames = vec![prop.get_name()];
+    fnames.extend(
+        property_fnames_mut(&mut prop)
+            .into_iter()
+            .map(|fname| fname.clone()),
+    );
+    fnames
+}
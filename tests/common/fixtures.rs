@@ -0,0 +1,492 @@
+//! Builds the test levels byte by byte in the layout Unreal Engine 5.1 cooks packages in, then has
+//! unreal_asset write them back so that they come back byte for byte from --roundtrip. Only the
+//! fields uedit edits carry data, everything else is left empty or zero.
+
+use std::fs;
+use std::path::Path;
+
+use super::open;
+use super::write;
+
+const PACKAGE_FILE_TAG: u32 = 0x9e2a83c1;
+/// PKG_Cooked | PKG_ContainsMap | PKG_FilterEditorOnly, with tagged rather than unversioned
+/// properties so that no mappings are needed to read them
+const PACKAGE_FLAGS: u32 = 0x0000_0200 | 0x0002_0000 | 0x8000_0000;
+const RF_PUBLIC: u32 = 0x1;
+const RF_STANDALONE: u32 = 0x2;
+const RF_TRANSACTIONAL: u32 = 0x8;
+
+/// Writes the level fixtures into dir, named as in tests/fixtures/README.md
+pub fn generate(dir: &Path) {
+    let raw_dir = dir.join("raw");
+    fs::create_dir_all(&raw_dir).unwrap();
+    let levels = [
+        (
+            "UeditTestMap",
+            vec![[100.0, 200.0, 300.0], [-500.0, 0.0, 50.0]],
+        ),
+        ("UeditTestDonor", vec![[0.0, 0.0, 100.0]]),
+    ];
+    for (package_name, actor_locations) in levels {
+        let (uasset, uexp) = level_package(package_name, &actor_locations).write();
+        let raw_path = raw_dir.join(package_name).with_extension("uasset");
+        fs::write(&raw_path, uasset).unwrap();
+        fs::write(raw_path.with_extension("uexp"), uexp).unwrap();
+        // unreal_asset lays some of the header out differently than written above, so the
+        // fixture is its own output
+        write(
+            &open(&raw_path),
+            &dir.join(package_name).with_extension("uasset"),
+        );
+    }
+}
+
+/// A level with one actor per location, each with a SceneComponent at that location as its root
+fn level_package(package_name: &str, actor_locations: &[[f64; 3]]) -> Package {
+    let mut package = Package::default();
+    // the package path is in the name map of every cooked map, which is what makes uedit rename it
+    package.names.add(&format!("/Game/{}", package_name));
+    let engine = package.import("/Script/CoreUObject", "Package", 0, "/Script/Engine");
+    let mut classes = vec![];
+    for class in ["World", "Level", "Actor", "SceneComponent"] {
+        let class_index = package.import("/Script/CoreUObject", "Class", engine, class);
+        let template = package.import(
+            "/Script/Engine",
+            class,
+            engine,
+            &format!("Default__{}", class),
+        );
+        classes.push((class_index, template));
+    }
+    let [world_class, level_class, actor_class, component_class] = classes[..] else {
+        unreachable!();
+    };
+
+    let world = 1;
+    let level = 2;
+    let actors: Vec<i32> = (0..actor_locations.len() as i32)
+        .map(|i| level + 1 + i * 2)
+        .collect();
+
+    let mut data = Writer::default();
+    data.end_properties(&mut package.names);
+    // PersistentLevel, ExtraReferencedObjects and StreamingLevels
+    data.i32(level);
+    data.i32(0);
+    data.i32(0);
+    package.export(Export {
+        class: world_class,
+        outer: 0,
+        name: (package_name.to_string(), 0),
+        flags: RF_PUBLIC | RF_STANDALONE | RF_TRANSACTIONAL,
+        is_asset: true,
+        data,
+        create_before_serialization: vec![level],
+    });
+
+    let mut data = Writer::default();
+    data.end_properties(&mut package.names);
+    data.i32(actors.len() as i32);
+    for actor in &actors {
+        data.i32(*actor);
+    }
+    // URL: protocol, host, map, portal, options, port and whether it's valid
+    data.fstring("unreal");
+    data.fstring("");
+    data.fstring(&format!("/Game/{}", package_name));
+    data.fstring("");
+    data.i32(0);
+    data.i32(7777);
+    data.i32(1);
+    // model, model components, level script actor, nav list start and end
+    data.i32(0);
+    data.i32(0);
+    data.i32(0);
+    data.i32(0);
+    data.i32(0);
+    // empty precomputed visibility handler and volume distance field
+    data.zeros(36 + 69);
+    package.export(Export {
+        class: level_class,
+        outer: world,
+        name: ("PersistentLevel".to_string(), 0),
+        flags: RF_TRANSACTIONAL,
+        is_asset: false,
+        data,
+        create_before_serialization: actors.clone(),
+    });
+
+    for (i, (actor, location)) in actors.iter().zip(actor_locations).enumerate() {
+        let component = actor + 1;
+        let mut data = Writer::default();
+        data.object_property(&mut package.names, "RootComponent", component);
+        data.end_properties(&mut package.names);
+        package.export(Export {
+            class: actor_class,
+            outer: level,
+            // FName number n is shown as _(n-1), so the actors are Actor_0, Actor_1 and so on
+            name: ("Actor".to_string(), i as i32 + 1),
+            flags: RF_TRANSACTIONAL,
+            is_asset: false,
+            data,
+            create_before_serialization: vec![component],
+        });
+
+        let mut data = Writer::default();
+        data.vector_property(&mut package.names, "RelativeLocation", *location);
+        data.end_properties(&mut package.names);
+        package.export(Export {
+            class: component_class,
+            outer: *actor,
+            name: ("DefaultSceneRoot".to_string(), 0),
+            flags: RF_TRANSACTIONAL,
+            is_asset: false,
+            data,
+            create_before_serialization: vec![],
+        });
+    }
+
+    package
+}
+
+#[derive(Default)]
+struct Names {
+    names: Vec<String>,
+}
+
+impl Names {
+    fn add(&mut self, name: &str) -> i32 {
+        match self.names.iter().position(|existing| existing == name) {
+            Some(index) => index as i32,
+            None => {
+                self.names.push(name.to_string());
+                self.names.len() as i32 - 1
+            }
+        }
+    }
+}
+
+struct Import {
+    class_package: i32,
+    class_name: i32,
+    outer: i32,
+    object_name: i32,
+}
+
+struct Export {
+    /// Class import and the default object import it's created from
+    class: (i32, i32),
+    outer: i32,
+    name: (String, i32),
+    flags: u32,
+    is_asset: bool,
+    data: Writer,
+    create_before_serialization: Vec<i32>,
+}
+
+struct PackageExport {
+    class: i32,
+    template: i32,
+    outer: i32,
+    name: (i32, i32),
+    flags: u32,
+    is_asset: bool,
+    data: Vec<u8>,
+    create_before_serialization: Vec<i32>,
+    serialization_before_create: Vec<i32>,
+    create_before_create: Vec<i32>,
+}
+
+impl PackageExport {
+    /// Returns the preload dependencies in the order they're stored in the package
+    fn dependencies(&self) -> impl Iterator<Item = &i32> {
+        self.create_before_serialization
+            .iter()
+            .chain(&self.serialization_before_create)
+            .chain(&self.create_before_create)
+    }
+}
+
+#[derive(Default)]
+struct Package {
+    names: Names,
+    imports: Vec<Import>,
+    exports: Vec<PackageExport>,
+}
+
+impl Package {
+    /// Adds an import and returns its index
+    fn import(&mut self, class_package: &str, class_name: &str, outer: i32, name: &str) -> i32 {
+        self.imports.push(Import {
+            class_package: self.names.add(class_package),
+            class_name: self.names.add(class_name),
+            outer,
+            object_name: self.names.add(name),
+        });
+        -(self.imports.len() as i32)
+    }
+
+    fn export(&mut self, export: Export) {
+        let name = (self.names.add(&export.name.0), export.name.1);
+        let (class, template) = export.class;
+        self.exports.push(PackageExport {
+            class,
+            template,
+            outer: export.outer,
+            name,
+            flags: export.flags,
+            is_asset: export.is_asset,
+            data: export.data.bytes,
+            create_before_serialization: export.create_before_serialization,
+            serialization_before_create: vec![class, template],
+            // outers are created before what they contain
+            create_before_create: match export.outer {
+                0 => vec![],
+                outer => vec![outer],
+            },
+        });
+    }
+
+    /// Returns the .uasset and .uexp of the package
+    fn write(&self) -> (Vec<u8>, Vec<u8>) {
+        let num_names = self.names.names.len() as i32;
+        let num_exports = self.exports.len() as i32;
+        let mut header = Writer::default();
+        header.u32(PACKAGE_FILE_TAG);
+        // legacy file version and legacy UE3 version
+        header.i32(-8);
+        header.i32(0);
+        // UE4, UE5 and licensee versions, all 0 since cooked packages are unversioned
+        header.i32(0);
+        header.i32(0);
+        header.i32(0);
+        // custom versions
+        header.i32(0);
+        let total_header_size = header.placeholder_i32();
+        header.fstring("None");
+        header.u32(PACKAGE_FLAGS);
+        header.i32(num_names);
+        let name_offset = header.placeholder_i32();
+        // soft object paths and gatherable text data
+        header.zeros(16);
+        header.i32(num_exports);
+        let export_offset = header.placeholder_i32();
+        header.i32(self.imports.len() as i32);
+        let import_offset = header.placeholder_i32();
+        let depends_offset = header.placeholder_i32();
+        // soft package references, searchable names and thumbnail table
+        header.zeros(16);
+        // package guid
+        header.zeros(16);
+        // one generation
+        header.i32(1);
+        header.i32(num_exports);
+        header.i32(num_names);
+        // saved by and compatible with engine version
+        for _ in 0..2 {
+            header.u16(5);
+            header.u16(1);
+            header.u16(1);
+            header.u32(0);
+            header.fstring("++UE5+Release-5.1");
+        }
+        // compression flags, compressed chunks, package source and additional packages to cook
+        header.zeros(16);
+        let asset_registry_data_offset = header.placeholder_i32();
+        let bulk_data_start_offset = header.position();
+        header.i64(0);
+        // world tile info offset and chunk ids
+        header.zeros(8);
+        let num_dependencies: usize = self
+            .exports
+            .iter()
+            .map(|export| export.dependencies().count())
+            .sum();
+        header.i32(num_dependencies as i32);
+        let preload_dependency_offset = header.placeholder_i32();
+        // names referenced from export data and payload table of contents offset
+        header.i32(num_names);
+        header.i64(-1);
+
+        header.patch_i32(name_offset, header.position() as i32);
+        for name in &self.names.names {
+            header.fstring(name);
+            // non-case-preserving and case-preserving hashes, which unreal_asset doesn't check
+            header.u16(0);
+            header.u16(0);
+        }
+
+        header.patch_i32(import_offset, header.position() as i32);
+        for import in &self.imports {
+            header.fname(import.class_package, 0);
+            header.fname(import.class_name, 0);
+            header.i32(import.outer);
+            header.fname(import.object_name, 0);
+            // optional
+            header.i32(0);
+        }
+
+        header.patch_i32(export_offset, header.position() as i32);
+        let mut serial_offsets = vec![];
+        let mut first_dependency = 0;
+        for export in &self.exports {
+            header.i32(export.class);
+            // super
+            header.i32(0);
+            header.i32(export.template);
+            header.i32(export.outer);
+            header.fname(export.name.0, export.name.1);
+            header.u32(export.flags);
+            header.i64(export.data.len() as i64);
+            serial_offsets.push(header.position());
+            header.i64(0);
+            // forced export, not for client, not for server, inherited instance, package flags
+            // and not always loaded for editor game
+            header.zeros(24);
+            header.i32(export.is_asset as i32);
+            // generate public hash
+            header.i32(0);
+            header.i32(first_dependency);
+            // serialization before serialization, create before serialization, serialization
+            // before create and create before create
+            header.i32(0);
+            header.i32(export.create_before_serialization.len() as i32);
+            header.i32(export.serialization_before_create.len() as i32);
+            header.i32(export.create_before_create.len() as i32);
+            first_dependency += export.dependencies().count() as i32;
+        }
+
+        // cooked packages keep an empty depends map
+        header.patch_i32(depends_offset, header.position() as i32);
+        for _ in &self.exports {
+            header.i32(0);
+        }
+
+        // no asset registry objects
+        header.patch_i32(asset_registry_data_offset, header.position() as i32);
+        header.i32(0);
+
+        header.patch_i32(preload_dependency_offset, header.position() as i32);
+        for export in &self.exports {
+            for dependency in export.dependencies() {
+                header.i32(*dependency);
+            }
+        }
+
+        // export offsets count from the start of the .uasset as if the two were one file
+        let header_size = header.position();
+        header.patch_i32(total_header_size, header_size as i32);
+        let mut uexp = Writer::default();
+        for (export, serial_offset) in self.exports.iter().zip(serial_offsets) {
+            header.patch_i64(serial_offset, (header_size + uexp.position()) as i64);
+            uexp.bytes.extend_from_slice(&export.data);
+        }
+        header.patch_i64(
+            bulk_data_start_offset,
+            (header_size + uexp.position()) as i64,
+        );
+        uexp.u32(PACKAGE_FILE_TAG);
+        (header.bytes, uexp.bytes)
+    }
+}
+
+/// Little endian writer for the package header and export data
+#[derive(Default)]
+struct Writer {
+    bytes: Vec<u8>,
+}
+
+impl Writer {
+    fn position(&self) -> usize {
+        self.bytes.len()
+    }
+
+    fn u16(&mut self, value: u16) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn i32(&mut self, value: i32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn i64(&mut self, value: i64) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn f64(&mut self, value: f64) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn zeros(&mut self, len: usize) {
+        self.bytes.resize(self.bytes.len() + len, 0);
+    }
+
+    /// Writes a length-prefixed, null terminated string, or just a 0 length if it's empty
+    fn fstring(&mut self, value: &str) {
+        if value.is_empty() {
+            self.i32(0);
+            return;
+        }
+        self.i32(value.len() as i32 + 1);
+        self.bytes.extend_from_slice(value.as_bytes());
+        self.bytes.push(0);
+    }
+
+    fn fname(&mut self, name_index: i32, number: i32) {
+        self.i32(name_index);
+        self.i32(number);
+    }
+
+    /// Writes a 0 to be patched later and returns where it is
+    fn placeholder_i32(&mut self) -> usize {
+        let position = self.position();
+        self.i32(0);
+        position
+    }
+
+    fn patch_i32(&mut self, position: usize, value: i32) {
+        self.bytes[position..position + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn patch_i64(&mut self, position: usize, value: i64) {
+        self.bytes[position..position + 8].copy_from_slice(&value.to_le_bytes());
+    }
+
+    /// Writes a property tag: name, type, size and array index, then the type specific header
+    /// and no property guid
+    fn property_tag(&mut self, names: &mut Names, name: &str, property_type: &str, size: i32) {
+        self.fname(names.add(name), 0);
+        self.fname(names.add(property_type), 0);
+        self.i32(size);
+        self.i32(0);
+        if property_type == "StructProperty" {
+            self.fname(names.add("Vector"), 0);
+            // struct guid
+            self.zeros(16);
+        }
+        self.bytes.push(0);
+    }
+
+    fn object_property(&mut self, names: &mut Names, name: &str, value: i32) {
+        self.property_tag(names, name, "ObjectProperty", 4);
+        self.i32(value);
+    }
+
+    /// Writes a Vector struct property, in doubles since UE5's large world coordinates
+    fn vector_property(&mut self, names: &mut Names, name: &str, value: [f64; 3]) {
+        self.property_tag(names, name, "StructProperty", 24);
+        for component in value {
+            self.f64(component);
+        }
+    }
+
+    /// Ends the tagged properties of an object, followed by it having no object guid
+    fn end_properties(&mut self, names: &mut Names) {
+        self.fname(names.add("None"), 0);
+        self.i32(0);
+    }
+}
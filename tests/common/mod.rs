@@ -0,0 +1,231 @@
+// each test file uses only some of these helpers
+#![allow(dead_code)]
+
+mod fixtures;
+
+use std::fs;
+use std::fs::File;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::OnceLock;
+use unreal_asset::exports::Export;
+use unreal_asset::exports::ExportBaseTrait;
+use unreal_asset::exports::ExportNormalTrait;
+use unreal_asset::properties::Property;
+use unreal_asset::types::PackageIndex;
+use unreal_asset::Asset;
+
+/// Level with at least one actor that has a RootComponent with a RelativeLocation. The name
+/// shouldn't appear in any other name, since uedit replaces it when the output name differs.
+pub const LEVEL: &str = "UeditTestMap.uasset";
+/// Level with at least one actor to transplant into LEVEL
+pub const DONOR: &str = "UeditTestDonor.uasset";
+
+/// Returns the path of a fixture. A cooked map with the fixture's name in tests/fixtures is used
+/// if there is one, otherwise the fixtures are generated once per test run.
+pub fn fixture(name: &str) -> PathBuf {
+    static GENERATED: OnceLock<PathBuf> = OnceLock::new();
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(name);
+    if path.exists() {
+        return path;
+    }
+    let dir = GENERATED.get_or_init(|| {
+        let dir = out_dir("fixtures");
+        fixtures::generate(&dir);
+        dir
+    });
+    dir.join(name)
+}
+
+/// Returns an empty directory for a test to write its output into
+pub fn out_dir(test_name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("uedit-{}-{}", test_name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Runs uedit and returns whether it succeeded along with its stdout and stderr
+pub fn run_uedit(args: &[&str]) -> (bool, String, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_uedit"))
+        .args(args)
+        .output()
+        .unwrap();
+    (
+        output.status.success(),
+        String::from_utf8_lossy(&output.stdout).to_string(),
+        String::from_utf8_lossy(&output.stderr).to_string(),
+    )
+}
+
+/// Runs uedit and panics with its output if it fails
+pub fn uedit(args: &[&str]) -> String {
+    let (success, stdout, stderr) = run_uedit(args);
+    if !success {
+        panic!(
+            "uedit {:?} failed\nstdout:\n{}\nstderr:\n{}",
+            args, stdout, stderr
+        );
+    }
+    stdout
+}
+
+/// Runs uedit and panics with its output if it succeeds, returning its stderr
+pub fn uedit_fails(args: &[&str]) -> String {
+    let (success, stdout, stderr) = run_uedit(args);
+    if success {
+        panic!("uedit {:?} should have failed\nstdout:\n{}", args, stdout);
+    }
+    stderr
+}
+
+pub fn open(uasset_path: &Path) -> Asset<File> {
+    Asset::new(
+        File::open(uasset_path).unwrap(),
+        File::open(uasset_path.with_extension("uexp")).ok(),
+        unreal_asset::engine_version::EngineVersion::VER_UE5_1,
+        None,
+    )
+    .unwrap()
+}
+
+/// Writes an asset to a .uasset file and a .uexp file next to it
+pub fn write(asset: &Asset<File>, uasset_path: &Path) {
+    asset
+        .write_data(
+            &mut File::create(uasset_path).unwrap(),
+            Some(&mut File::create(uasset_path.with_extension("uexp")).unwrap()),
+        )
+        .unwrap();
+}
+
+/// Writes a copy of the level whose first actor's RootComponent points past the last export,
+/// which --validate reports as an error
+pub fn write_dangling_root_component(level: &Path, uasset_path: &Path) -> (i32, i32) {
+    let mut asset = open(level);
+    let (actor, _) = first_actor_with_root(&asset);
+    let target = asset.asset_data.exports.len() as i32 + 100;
+    let normal_export = asset
+        .get_export_mut(PackageIndex::new(actor))
+        .unwrap()
+        .get_normal_export_mut()
+        .unwrap();
+    for prop in &mut normal_export.properties {
+        if let Property::ObjectProperty(prop) = prop {
+            if prop.name.get_owned_content() == "RootComponent" {
+                prop.value = PackageIndex::new(target);
+            }
+        }
+    }
+    write(&asset, uasset_path);
+    (actor, target)
+}
+
+pub fn path_str(path: &Path) -> &str {
+    path.to_str().unwrap()
+}
+
+pub fn export_name(asset: &Asset<File>, index: i32) -> String {
+    asset
+        .get_export(PackageIndex::new(index))
+        .unwrap()
+        .get_base_export()
+        .object_name
+        .get_owned_content()
+}
+
+/// Returns an export's name and FName number
+pub fn export_fname(asset: &Asset<File>, index: i32) -> (String, i32) {
+    let object_name = &asset
+        .get_export(PackageIndex::new(index))
+        .unwrap()
+        .get_base_export()
+        .object_name;
+    (object_name.get_owned_content(), object_name.get_number())
+}
+
+pub fn export_outer(asset: &Asset<File>, index: i32) -> i32 {
+    asset
+        .get_export(PackageIndex::new(index))
+        .unwrap()
+        .get_base_export()
+        .outer_index
+        .index
+}
+
+/// Returns the index of the PersistentLevel export, or the only level
+pub fn level_index(asset: &Asset<File>) -> i32 {
+    let levels: Vec<i32> = asset
+        .asset_data
+        .exports
+        .iter()
+        .enumerate()
+        .filter(|(_, export)| matches!(export, Export::LevelExport(_)))
+        .map(|(i, _)| i as i32 + 1)
+        .collect();
+    *levels
+        .iter()
+        .find(|index| export_name(asset, **index) == "PersistentLevel")
+        .or(levels.first())
+        .expect("fixture has no level")
+}
+
+pub fn level_actors(asset: &Asset<File>) -> Vec<i32> {
+    let Some(Export::LevelExport(level)) = asset.get_export(PackageIndex::new(level_index(asset)))
+    else {
+        unreachable!();
+    };
+    level
+        .actors
+        .iter()
+        .map(|actor| actor.index)
+        .filter(|index| *index > 0)
+        .collect()
+}
+
+pub fn root_component(asset: &Asset<File>, actor_index: i32) -> Option<i32> {
+    let normal_export = asset
+        .get_export(PackageIndex::new(actor_index))?
+        .get_normal_export()?;
+    normal_export.properties.iter().find_map(|prop| match prop {
+        Property::ObjectProperty(prop)
+            if prop.name.get_owned_content() == "RootComponent" && prop.value.index > 0 =>
+        {
+            Some(prop.value.index)
+        }
+        _ => None,
+    })
+}
+
+/// Returns the first actor in the level that has a root component
+pub fn first_actor_with_root(asset: &Asset<File>) -> (i32, i32) {
+    level_actors(asset)
+        .into_iter()
+        .find_map(|actor| Some((actor, root_component(asset, actor)?)))
+        .expect("fixture level has no actor with a RootComponent")
+}
+
+/// Reads the vector inside a struct property such as RelativeLocation
+pub fn struct_vector(asset: &Asset<File>, export_index: i32, name: &str) -> Option<[f64; 3]> {
+    let normal_export = asset
+        .get_export(PackageIndex::new(export_index))?
+        .get_normal_export()?;
+    normal_export.properties.iter().find_map(|prop| {
+        let Property::StructProperty(prop) = prop else {
+            return None;
+        };
+        if prop.name.get_owned_content() != name {
+            return None;
+        }
+        prop.value.iter().find_map(|prop| match prop {
+            Property::VectorProperty(prop) => {
+                Some([prop.value.x.0, prop.value.y.0, prop.value.z.0])
+            }
+            _ => None,
+        })
+    })
+}
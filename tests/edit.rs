@@ -0,0 +1,563 @@
+mod common;
+
+use common::*;
+use std::fs::File;
+use unreal_asset::exports::Export;
+use unreal_asset::exports::ExportBaseTrait;
+use unreal_asset::types::PackageIndex;
+use unreal_asset::Asset;
+
+#[test]
+fn package_name_is_rewritten_from_output_name() {
+    let level = fixture(LEVEL);
+    let output = out_dir("package_name").join("Renamed.uasset");
+    uedit(&["-i", path_str(&level), "-o", path_str(&output)]);
+
+    let asset = open(&output);
+    let name_map = asset.get_name_map();
+    let name_map = name_map.borrow();
+    let names = name_map.get_name_map_index_list();
+    assert!(names.iter().any(|name| name.contains("Renamed")));
+    assert!(!names.iter().any(|name| name.contains("UeditTestMap")));
+}
+
+#[test]
+fn disable_import_zeroes_outer() {
+    let level = fixture(LEVEL);
+    let input = open(&level);
+    let name = input
+        .imports
+        .iter()
+        .find(|import| import.outer_index.index != 0)
+        .expect("fixture has no import with an outer")
+        .object_name
+        .get_owned_content();
+    let output = out_dir("disable_import").join(LEVEL);
+    uedit(&[
+        "-i",
+        path_str(&level),
+        "-o",
+        path_str(&output),
+        "--disable-import",
+        &name,
+    ]);
+
+    let asset = open(&output);
+    assert_eq!(asset.imports.len(), input.imports.len());
+    for import in &asset.imports {
+        if import.object_name.get_owned_content() == name {
+            assert_eq!(import.outer_index.index, 0);
+        }
+    }
+}
+
+#[test]
+fn rename_import_changes_object_name() {
+    let level = fixture(LEVEL);
+    let input = open(&level);
+    let name = input
+        .imports
+        .iter()
+        .find(|import| import.class_name.get_owned_content() != "Package")
+        .expect("fixture has no non-package import")
+        .object_name
+        .get_owned_content();
+    let output = out_dir("rename_import").join(LEVEL);
+    uedit(&[
+        "-i",
+        path_str(&level),
+        "-o",
+        path_str(&output),
+        "--rename-import",
+        &format!("{}>RenamedImport", name),
+    ]);
+
+    let asset = open(&output);
+    // only the first import with the name is renamed
+    let first = input
+        .imports
+        .iter()
+        .position(|import| import.object_name.get_owned_content() == name)
+        .unwrap();
+    assert_eq!(
+        asset.imports[first].object_name.get_owned_content(),
+        "RenamedImport"
+    );
+}
+
+#[test]
+fn disable_actor_removes_it_from_level_only() {
+    let level = fixture(LEVEL);
+    let input = open(&level);
+    let actor = level_actors(&input)[0];
+    let output = out_dir("disable_actor").join(LEVEL);
+    uedit(&[
+        "-i",
+        path_str(&level),
+        "-o",
+        path_str(&output),
+        "--disable-actor-by-index",
+        &actor.to_string(),
+    ]);
+
+    let asset = open(&output);
+    assert!(!level_actors(&asset).contains(&actor));
+    assert_eq!(level_actors(&asset).len(), level_actors(&input).len() - 1);
+    assert_eq!(
+        asset.asset_data.exports.len(),
+        input.asset_data.exports.len()
+    );
+    assert_eq!(export_name(&asset, actor), export_name(&input, actor));
+}
+
+#[test]
+fn edit_export_sets_vector_property() {
+    let level = fixture(LEVEL);
+    let input = open(&level);
+    let (_, component) = first_actor_with_root(&input);
+    assert!(
+        struct_vector(&input, component, "RelativeLocation").is_some(),
+        "fixture actor's root component has no RelativeLocation"
+    );
+    let output = out_dir("edit_export").join(LEVEL);
+    uedit(&[
+        "-i",
+        path_str(&level),
+        "-o",
+        path_str(&output),
+        "--edit-export",
+        &format!("{}.RelativeLocation.RelativeLocation=1,2,3", component),
+    ]);
+
+    let asset = open(&output);
+    assert_eq!(
+        struct_vector(&asset, component, "RelativeLocation"),
+        Some([1.0, 2.0, 3.0])
+    );
+}
+
+#[test]
+fn move_actor_adds_offset_to_location() {
+    let level = fixture(LEVEL);
+    let input = open(&level);
+    let (actor, component) = first_actor_with_root(&input);
+    let [x, y, z] = struct_vector(&input, component, "RelativeLocation").unwrap_or_default();
+    let output = out_dir("move_actor").join(LEVEL);
+    uedit(&[
+        "-i",
+        path_str(&level),
+        "-o",
+        path_str(&output),
+        "--move-actor",
+        &format!("{}+=10,20,30", actor),
+    ]);
+
+    let asset = open(&output);
+    assert_eq!(
+        struct_vector(&asset, component, "RelativeLocation"),
+        Some([x + 10.0, y + 20.0, z + 30.0])
+    );
+}
+
+#[test]
+fn transplant_adds_actor_and_subobjects_to_level() {
+    let (level, donor) = (fixture(LEVEL), fixture(DONOR));
+    let input = open(&level);
+    let donor_asset = open(&donor);
+    let donor_actor = level_actors(&donor_asset)[0];
+    let output = out_dir("transplant").join(LEVEL);
+    uedit(&[
+        "-i",
+        path_str(&level),
+        "-o",
+        path_str(&output),
+        "--transplant-donor",
+        path_str(&donor),
+        "--actor-to-transplant",
+        &donor_actor.to_string(),
+    ]);
+
+    let asset = open(&output);
+    let num_exports = input.asset_data.exports.len() as i32;
+    // the actor is always the first transplanted export
+    let actor = num_exports + 1;
+    assert!(asset.asset_data.exports.len() as i32 > num_exports);
+    assert!(level_actors(&asset).contains(&actor));
+    assert_eq!(
+        export_name(&asset, actor),
+        export_name(&donor_asset, donor_actor)
+    );
+    let actor_export = asset.get_export(PackageIndex::new(actor)).unwrap();
+    assert_eq!(
+        actor_export.get_base_export().outer_index.index,
+        level_index(&asset)
+    );
+    // every reference in the transplanted exports has to resolve in the target
+    for export in &asset.asset_data.exports[num_exports as usize..] {
+        let base_export = export.get_base_export();
+        for index in [
+            base_export.class_index,
+            base_export.template_index,
+            base_export.outer_index,
+        ] {
+            assert!(index.index <= asset.asset_data.exports.len() as i32);
+            assert!(-index.index <= asset.imports.len() as i32);
+        }
+    }
+    let Some(Export::LevelExport(level_export)) =
+        asset.get_export(PackageIndex::new(level_index(&asset)))
+    else {
+        unreachable!();
+    };
+    assert!(level_export
+        .get_base_export()
+        .create_before_serialization_dependencies
+        .iter()
+        .any(|dep| dep.index == actor));
+}
+
+#[test]
+fn undo_journal_restores_disabled_import() {
+    let level = fixture(LEVEL);
+    let input = open(&level);
+    let (index, name) = input
+        .imports
+        .iter()
+        .enumerate()
+        .find(|(_, import)| import.outer_index.index != 0)
+        .map(|(i, import)| (i, import.object_name.get_owned_content()))
+        .expect("fixture has no import with an outer");
+    let dir = out_dir("undo");
+    let edited = dir.join(LEVEL);
+    uedit(&[
+        "-i",
+        path_str(&level),
+        "-o",
+        path_str(&edited),
+        "--disable-import",
+        &name,
+    ]);
+    let restored = dir.join("Restored").join(LEVEL);
+    std::fs::create_dir_all(restored.parent().unwrap()).unwrap();
+    uedit(&[
+        "-i",
+        path_str(&edited),
+        "-o",
+        path_str(&restored),
+        "--undo",
+        path_str(&edited.with_extension("undo.json")),
+    ]);
+
+    let asset = open(&restored);
+    assert_eq!(
+        asset.imports[index].outer_index.index,
+        input.imports[index].outer_index.index
+    );
+}
+
+#[test]
+fn dry_run_writes_nothing() {
+    let level = fixture(LEVEL);
+    let input = open(&level);
+    let actor = level_actors(&input)[0];
+    let output = out_dir("dry_run").join(LEVEL);
+    let stdout = uedit(&[
+        "-i",
+        path_str(&level),
+        "-o",
+        path_str(&output),
+        "--dry-run",
+        "--disable-actor-by-index",
+        &actor.to_string(),
+    ]);
+
+    assert!(stdout.contains("Dry run"));
+    assert!(!output.exists());
+}
+
+#[test]
+fn fixtures_validate_cleanly() {
+    for name in [LEVEL, DONOR] {
+        uedit(&["-i", path_str(&fixture(name)), "--validate"]);
+    }
+}
+
+#[test]
+fn add_import_adds_object_under_new_package() {
+    let level = fixture(LEVEL);
+    let input = open(&level);
+    let output = out_dir("add_import").join(LEVEL);
+    uedit(&[
+        "-i",
+        path_str(&level),
+        "-o",
+        path_str(&output),
+        "--add-import",
+        "/Script/Engine,StaticMesh,/Game/UeditAddedMesh,UeditAddedMesh",
+    ]);
+
+    let asset = open(&output);
+    assert_eq!(asset.imports.len(), input.imports.len() + 2);
+    let (index, mesh) = asset
+        .imports
+        .iter()
+        .enumerate()
+        .find(|(_, import)| {
+            import.object_name.get_owned_content() == "UeditAddedMesh"
+                && import.class_name.get_owned_content() == "StaticMesh"
+        })
+        .expect("added import not found");
+    assert!(index >= input.imports.len());
+    let package = asset.get_import(mesh.outer_index).unwrap();
+    assert_eq!(package.class_name.get_owned_content(), "Package");
+    assert_eq!(
+        package.object_name.get_owned_content(),
+        "/Game/UeditAddedMesh"
+    );
+}
+
+#[test]
+fn remove_import_drops_unreferenced_import() {
+    let level = fixture(LEVEL);
+    let input = open(&level);
+    let dir = out_dir("remove_import");
+    let added = dir.join("Added").join(LEVEL);
+    std::fs::create_dir_all(added.parent().unwrap()).unwrap();
+    uedit(&[
+        "-i",
+        path_str(&level),
+        "-o",
+        path_str(&added),
+        "--add-import",
+        "/Script/Engine,StaticMesh,/Game/UeditAddedMesh,UeditAddedMesh",
+    ]);
+    let output = dir.join(LEVEL);
+    uedit(&[
+        "-i",
+        path_str(&added),
+        "-o",
+        path_str(&output),
+        "--remove-import",
+        "UeditAddedMesh",
+        "--remove-import",
+        "/Game/UeditAddedMesh",
+    ]);
+
+    let asset = open(&output);
+    assert_eq!(asset.imports.len(), input.imports.len());
+    for (import, input_import) in asset.imports.iter().zip(&input.imports) {
+        assert_eq!(import.object_name, input_import.object_name);
+        assert_eq!(import.outer_index, input_import.outer_index);
+    }
+}
+
+#[test]
+fn remove_import_refuses_to_null_a_class() {
+    let level = fixture(LEVEL);
+    let input = open(&level);
+    let actor = level_actors(&input)[0];
+    let class_index = input
+        .get_export(PackageIndex::new(actor))
+        .unwrap()
+        .get_base_export()
+        .class_index;
+    let class_name = input
+        .get_import(class_index)
+        .expect("fixture actor's class isn't an import")
+        .object_name
+        .get_owned_content();
+    let output = out_dir("remove_class_import").join(LEVEL);
+    let stderr = uedit_fails(&[
+        "-i",
+        path_str(&level),
+        "-o",
+        path_str(&output),
+        "--remove-import",
+        &format!("{}>null", class_name),
+    ]);
+
+    assert!(stderr.contains("can't be nulled"));
+    assert!(!output.exists());
+}
+
+#[test]
+fn delete_actor_removes_it_and_its_subobjects() {
+    let level = fixture(LEVEL);
+    let input = open(&level);
+    let (actor, component) = first_actor_with_root(&input);
+    let actor_fname = export_fname(&input, actor);
+    let component_fname = export_fname(&input, component);
+    let output = out_dir("delete_actor").join(LEVEL);
+    uedit(&[
+        "-i",
+        path_str(&level),
+        "-o",
+        path_str(&output),
+        "--delete-actor-by-index",
+        &actor.to_string(),
+    ]);
+
+    let asset = open(&output);
+    assert!(asset.asset_data.exports.len() <= input.asset_data.exports.len() - 2);
+    assert_eq!(level_actors(&asset).len(), level_actors(&input).len() - 1);
+    let count = |asset: &Asset<File>, fname: &(String, i32)| {
+        (1..=asset.asset_data.exports.len() as i32)
+            .filter(|index| export_fname(asset, *index) == *fname)
+            .count()
+    };
+    assert_eq!(count(&asset, &actor_fname), count(&input, &actor_fname) - 1);
+    assert_eq!(
+        count(&asset, &component_fname),
+        count(&input, &component_fname) - 1
+    );
+    // indices after the deleted exports shift down, so every level actor has to still resolve
+    for actor in level_actors(&asset) {
+        assert_eq!(export_outer(&asset, actor), level_index(&asset));
+    }
+}
+
+#[test]
+fn delete_actor_refuses_export_that_isnt_a_level_actor() {
+    let level = fixture(LEVEL);
+    let input = open(&level);
+    let (_, component) = first_actor_with_root(&input);
+    let output = out_dir("delete_component").join(LEVEL);
+    uedit_fails(&[
+        "-i",
+        path_str(&level),
+        "-o",
+        path_str(&output),
+        "--delete-actor-by-index",
+        &component.to_string(),
+    ]);
+
+    assert!(!output.exists());
+}
+
+#[test]
+fn duplicate_actor_adds_uniquely_named_copies_to_level() {
+    let level = fixture(LEVEL);
+    let input = open(&level);
+    let (actor, component) = first_actor_with_root(&input);
+    let [x, y, z] = struct_vector(&input, component, "RelativeLocation").unwrap_or_default();
+    let output = out_dir("duplicate_actor").join(LEVEL);
+    uedit(&[
+        "-i",
+        path_str(&level),
+        "-o",
+        path_str(&output),
+        "--duplicate-actor",
+        &format!("{}*2>100,0,0", actor),
+    ]);
+
+    let asset = open(&output);
+    let num_exports = input.asset_data.exports.len() as i32;
+    let copied = asset.asset_data.exports.len() as i32 - num_exports;
+    assert!(copied >= 4 && copied % 2 == 0);
+    let actors = level_actors(&asset);
+    assert_eq!(actors.len(), level_actors(&input).len() + 2);
+    let copies: Vec<i32> = actors
+        .into_iter()
+        .filter(|index| *index > num_exports)
+        .collect();
+    assert_eq!(copies.len(), 2);
+    let (name, number) = export_fname(&input, actor);
+    let mut numbers = vec![number];
+    for (i, copy) in copies.iter().enumerate() {
+        let (copy_name, copy_number) = export_fname(&asset, *copy);
+        assert_eq!(copy_name, name);
+        assert!(!numbers.contains(&copy_number));
+        numbers.push(copy_number);
+        let copy_component = root_component(&asset, *copy).unwrap();
+        let offset = 100.0 * (i + 1) as f64;
+        assert_eq!(
+            struct_vector(&asset, copy_component, "RelativeLocation"),
+            Some([x + offset, y, z])
+        );
+    }
+}
+
+#[test]
+fn copy_export_adds_donor_export_without_listing_it() {
+    let (level, donor) = (fixture(LEVEL), fixture(DONOR));
+    let input = open(&level);
+    let donor_asset = open(&donor);
+    let donor_actor = level_actors(&donor_asset)[0];
+    let level_export = level_index(&input);
+    let output = out_dir("copy_export").join(LEVEL);
+    uedit(&[
+        "-i",
+        path_str(&level),
+        "-o",
+        path_str(&output),
+        "--transplant-donor",
+        path_str(&donor),
+        "--copy-export",
+        &format!("{}>{}", donor_actor, level_export),
+    ]);
+
+    let asset = open(&output);
+    let copy = input.asset_data.exports.len() as i32 + 1;
+    assert!(asset.asset_data.exports.len() > input.asset_data.exports.len());
+    assert_eq!(
+        export_name(&asset, copy),
+        export_name(&donor_asset, donor_actor)
+    );
+    assert_eq!(export_outer(&asset, copy), level_export);
+    assert_eq!(level_actors(&asset), level_actors(&input));
+}
+
+#[test]
+fn in_place_edit_of_umap_keeps_its_extension() {
+    let level = fixture(LEVEL);
+    let input = open(&level);
+    let actor = level_actors(&input)[0];
+    let dir = out_dir("in_place_umap");
+    let umap = dir.join(LEVEL).with_extension("umap");
+    std::fs::copy(&level, &umap).unwrap();
+    std::fs::copy(level.with_extension("uexp"), umap.with_extension("uexp")).unwrap();
+    for _ in 0..2 {
+        uedit(&[
+            "-i",
+            path_str(&umap),
+            "--in-place",
+            "--disable-actor-by-index",
+            &actor.to_string(),
+        ]);
+    }
+
+    assert!(!umap.with_extension("uasset").exists());
+    assert_eq!(
+        std::fs::read(umap.with_extension("umap.bak")).unwrap(),
+        std::fs::read(&level).unwrap()
+    );
+    assert_eq!(
+        std::fs::read(umap.with_extension("uexp.bak")).unwrap(),
+        std::fs::read(level.with_extension("uexp")).unwrap()
+    );
+    assert!(umap.with_extension("umap.bak.1").exists());
+    assert!(umap.with_extension("uexp.bak.1").exists());
+    assert!(umap.with_extension("undo.json").exists());
+    assert!(umap.with_extension("undo.1.json").exists());
+    let asset = open(&umap);
+    assert!(!level_actors(&asset).contains(&actor));
+}
+
+#[test]
+fn errors_the_input_already_had_dont_stop_edits() {
+    let dir = out_dir("existing_errors");
+    let broken = dir.join("Broken").join(LEVEL);
+    std::fs::create_dir_all(broken.parent().unwrap()).unwrap();
+    let (actor, _) = write_dangling_root_component(&fixture(LEVEL), &broken);
+    let output = dir.join(LEVEL);
+    uedit(&[
+        "-i",
+        path_str(&broken),
+        "-o",
+        path_str(&output),
+        "--disable-actor-by-index",
+        &actor.to_string(),
+    ]);
+
+    assert!(!level_actors(&open(&output)).contains(&actor));
+}
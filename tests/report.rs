@@ -0,0 +1,119 @@
+mod common;
+
+use common::*;
+use std::fs;
+use unreal_asset::exports::ExportBaseTrait;
+use unreal_asset::types::PackageIndex;
+
+#[test]
+fn list_actors_reports_every_level_actor() {
+    let level = fixture(LEVEL);
+    let input = open(&level);
+    let stdout = uedit(&["-i", path_str(&level), "--list-actors", "--format", "json"]);
+
+    let reports: Vec<serde_json::Value> = serde_json::from_str(&stdout).unwrap();
+    for actor in level_actors(&input) {
+        let report = reports
+            .iter()
+            .find(|report| report["index"] == actor)
+            .unwrap_or_else(|| panic!("actor {} not listed", actor));
+        assert_eq!(report["level"], level_index(&input));
+        assert_eq!(report["in_actors_list"], true);
+        assert!(report["name"]
+            .as_str()
+            .unwrap()
+            .starts_with(&export_name(&input, actor)));
+    }
+    let (actor, component) = first_actor_with_root(&input);
+    let [x, y, z] = struct_vector(&input, component, "RelativeLocation").unwrap_or_default();
+    let report = reports
+        .iter()
+        .find(|report| report["index"] == actor)
+        .unwrap();
+    assert_eq!(report["location"]["x"], x);
+    assert_eq!(report["location"]["y"], y);
+    assert_eq!(report["location"]["z"], z);
+}
+
+#[test]
+fn roundtrip_reports_each_asset_and_fails_on_broken_ones() {
+    let dir = out_dir("roundtrip");
+    // levels are usually .umap, so one of the fixtures is copied as one
+    let copies = [
+        (fixture(LEVEL), dir.join(LEVEL)),
+        (fixture(DONOR), dir.join(DONOR).with_extension("umap")),
+    ];
+    for (path, copy) in &copies {
+        fs::copy(path, copy).unwrap();
+        fs::copy(path.with_extension("uexp"), copy.with_extension("uexp")).unwrap();
+    }
+    let level = fs::read(fixture(LEVEL)).unwrap();
+    fs::write(dir.join("Broken.uasset"), &level[..level.len() / 2]).unwrap();
+    fs::write(dir.join("notes.txt"), "not an asset").unwrap();
+    let (success, stdout, _) = run_uedit(&["-i", path_str(&dir), "--roundtrip"]);
+
+    assert!(!success);
+    for (_, copy) in &copies {
+        let name = copy.file_name().unwrap().to_str().unwrap();
+        assert!(stdout
+            .lines()
+            .any(|line| line.starts_with("OK: ") && line.ends_with(name)));
+    }
+    assert!(stdout
+        .lines()
+        .any(|line| line.starts_with("FAIL: ") && line.contains("Broken.uasset")));
+    assert!(stdout.contains("3 assets: 2 identical, 0 differ, 1 failed, 1 other files skipped"));
+}
+
+#[test]
+fn validate_reports_dangling_object_property() {
+    let broken = out_dir("validate_dangling").join(LEVEL);
+    let (actor, target) = write_dangling_root_component(&fixture(LEVEL), &broken);
+    let (success, stdout, _) =
+        run_uedit(&["-i", path_str(&broken), "--validate", "--format", "json"]);
+
+    assert!(!success);
+    let findings: Vec<serde_json::Value> = serde_json::from_str(&stdout).unwrap();
+    assert!(findings.iter().any(|finding| finding["severity"] == "error"
+        && finding["message"]
+            == format!(
+                "export {} RootComponent: ObjectProperty target {} does not exist",
+                actor, target
+            )));
+}
+
+#[test]
+fn validate_warns_about_disabled_import_still_in_use() {
+    let level = fixture(LEVEL);
+    let input = open(&level);
+    let actor = level_actors(&input)[0];
+    let class_index = input
+        .get_export(PackageIndex::new(actor))
+        .unwrap()
+        .get_base_export()
+        .class_index;
+    let class_name = input
+        .get_import(class_index)
+        .expect("fixture actor's class isn't an import")
+        .object_name
+        .get_owned_content();
+    let output = out_dir("validate_disabled").join(LEVEL);
+    uedit(&[
+        "-i",
+        path_str(&level),
+        "-o",
+        path_str(&output),
+        "--disable-import",
+        &class_name,
+    ]);
+    let stdout = uedit(&["-i", path_str(&output), "--validate", "--format", "json"]);
+
+    let findings: Vec<serde_json::Value> = serde_json::from_str(&stdout).unwrap();
+    assert!(findings
+        .iter()
+        .any(|finding| finding["severity"] == "warning"
+            && finding["message"].as_str().unwrap().starts_with(&format!(
+                "import {} \"{}\" has outer 0 but is still referenced",
+                class_index.index, class_name
+            ))));
+}